@@ -0,0 +1,185 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use svgpath::{Matrix, PathParser};
+
+/// Small deterministic generator, so every run measures the same input.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn coord(&mut self) -> String {
+        let n = (self.next() % 24000) as f64 / 1000.0 - 12.0;
+        let s = format!("{n:.3}");
+        let s = s.trim_end_matches('0').trim_end_matches('.');
+        // Minifiers drop the leading zero
+        if let Some(frac) = s.strip_prefix("0.") {
+            format!(".{frac}")
+        } else if let Some(frac) = s.strip_prefix("-0.") {
+            format!("-.{frac}")
+        } else {
+            s.to_string()
+        }
+    }
+}
+
+/// An icon set in the style of minified 24x24 icon fonts.
+fn icons(count: usize) -> Vec<String> {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    (0..count)
+        .map(|_| {
+            let mut d = format!("M{} {}", rng.coord(), rng.coord());
+            for _ in 0..(8 + rng.next() % 40) {
+                let c = match rng.next() % 10 {
+                    0 | 1 => format!("l{} {}", rng.coord(), rng.coord()),
+                    2 => format!("h{}", rng.coord()),
+                    3 => format!("v{}", rng.coord()),
+                    4 | 5 => format!(
+                        "c{} {} {} {} {} {}",
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord()
+                    ),
+                    6 => format!(
+                        "s{} {} {} {}",
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord()
+                    ),
+                    7 => format!(
+                        "q{} {} {} {}",
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord(),
+                        rng.coord()
+                    ),
+                    8 => format!(
+                        "a{} {} 0 {}{}{} {}",
+                        1 + rng.next() % 9,
+                        1 + rng.next() % 9,
+                        rng.next() % 2,
+                        rng.next() % 2,
+                        rng.coord(),
+                        rng.coord()
+                    ),
+                    _ => format!("zm{} {}", rng.coord(), rng.coord()),
+                };
+                d.push_str(&c);
+            }
+            d.push('z');
+            d
+        })
+        .collect()
+}
+
+/// A single large outline, like a country border from map data.
+fn map(points: usize) -> String {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut d = String::from("M 512.125 384.5 L");
+    for _ in 0..points {
+        d.push_str(&format!(" {},{}", rng.coord(), rng.coord()));
+    }
+    d.push_str(" Z");
+    d
+}
+
+fn parse(c: &mut Criterion) {
+    let icons = icons(2000);
+    let map = map(100_000);
+    let bytes = icons.iter().map(String::len).sum::<usize>();
+
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(bytes as u64));
+    group.bench_function(BenchmarkId::new("collect", "icons"), |b| {
+        b.iter(|| {
+            for d in &icons {
+                black_box(svgpath::parse(black_box(d)).unwrap());
+            }
+        })
+    });
+    group.bench_function(BenchmarkId::new("stream", "icons"), |b| {
+        b.iter(|| {
+            for d in &icons {
+                for cmd in PathParser::new(black_box(d)) {
+                    black_box(cmd.unwrap());
+                }
+            }
+        })
+    });
+    group.throughput(Throughput::Bytes(map.len() as u64));
+    group.bench_function(BenchmarkId::new("collect", "map"), |b| {
+        b.iter(|| black_box(svgpath::parse(black_box(&map)).unwrap()))
+    });
+    group.finish();
+}
+
+fn process(c: &mut Criterion) {
+    let paths = icons(2000)
+        .iter()
+        .map(|d| svgpath::parse(d).unwrap())
+        .collect::<Vec<_>>();
+    let simple = paths.iter().map(|p| p.simplify()).collect::<Vec<_>>();
+    let m = Matrix::new()
+        .translate(4.0, 2.0)
+        .rotate(30.0)
+        .scale(2.0, 0.5);
+
+    let mut group = c.benchmark_group("icons");
+    group.throughput(Throughput::Elements(paths.len() as u64));
+    group.bench_function("simplify", |b| {
+        b.iter(|| {
+            for p in &paths {
+                black_box(p.simplify());
+            }
+        })
+    });
+    group.bench_function("bbox", |b| {
+        b.iter(|| {
+            for p in &simple {
+                black_box(p.bbox());
+            }
+        })
+    });
+    group.bench_function("bbox_arcs", |b| {
+        b.iter(|| {
+            for p in &paths {
+                black_box(p.bbox());
+            }
+        })
+    });
+    group.bench_function("loose_bbox", |b| {
+        b.iter(|| {
+            for p in &simple {
+                black_box(p.loose_bbox());
+            }
+        })
+    });
+    group.bench_function("transform", |b| {
+        b.iter(|| {
+            for p in &simple {
+                black_box(p.transform(&m));
+            }
+        })
+    });
+    group.bench_function("to_string", |b| {
+        b.iter(|| {
+            for p in &simple {
+                black_box(p.to_string());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse, process);
+criterion_main!(benches);
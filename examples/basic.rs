@@ -17,7 +17,7 @@ fn main() {
     let sp = p.simplify();
 
     // Get the bounding box
-    let bb = sp.bbox();
+    let bb = sp.bbox().unwrap();
     println!("x: {}, y: {}", bb.min_x, bb.min_y);
     println!("width: {}, height: {}", bb.width(), bb.height());
     println!();
@@ -27,7 +27,7 @@ fn main() {
     let sp = sp.fit(&rect, true, true);
 
     // Rotate 35 degree by its center point
-    let center = sp.bbox().center();
+    let center = sp.bbox().unwrap().center();
     let m = svgpath::Matrix::new().rotate_by(35.0, center.x, center.y);
     let sp = sp.transform(&m);
 
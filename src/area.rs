@@ -0,0 +1,58 @@
+use crate::Command;
+use crate::segment::{self, Segment, gauss_legendre};
+
+/// Signed area and first moments of area, positive for subpaths that run
+/// clockwise on screen (y axis pointing down).
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Moments {
+    pub(crate) area: f64,
+    pub(crate) mx: f64,
+    pub(crate) my: f64,
+}
+
+impl Moments {
+    /// Contribution of one segment by Green's theorem.
+    ///
+    /// The integrands are polynomials of degree 8 at most, which Gauss-Legendre
+    /// integrates exactly.
+    fn add(&mut self, seg: &Segment) {
+        if let Segment::Line { from: a, to: b } = *seg {
+            self.area += (a.x * b.y - b.x * a.y) / 2.0;
+            self.mx += (b.y - a.y) * (a.x * a.x + a.x * b.x + b.x * b.x) / 6.0;
+            self.my -= (b.x - a.x) * (a.y * a.y + a.y * b.y + b.y * b.y) / 6.0;
+            return;
+        }
+        let at = |t: f64| (seg.point_at(t), seg.derivative(t));
+        self.area += gauss_legendre(
+            &|t| {
+                let (p, d) = at(t);
+                (p.x * d.y - p.y * d.x) / 2.0
+            },
+            0.0,
+            1.0,
+        );
+        self.mx += gauss_legendre(
+            &|t| {
+                let (p, d) = at(t);
+                p.x * p.x * d.y / 2.0
+            },
+            0.0,
+            1.0,
+        );
+        self.my -= gauss_legendre(
+            &|t| {
+                let (p, d) = at(t);
+                p.y * p.y * d.x / 2.0
+            },
+            0.0,
+            1.0,
+        );
+    }
+}
+
+/// Moments of `commands`, open subpaths are closed with a straight line.
+pub(crate) fn moments(commands: &[Command]) -> Moments {
+    let mut m = Moments::default();
+    segment::for_each_closed(commands, |seg| m.add(seg));
+    m
+}
@@ -1,3 +1,8 @@
+use std::f64::consts::PI;
+
+use crate::roots;
+use crate::segment::cubic_at;
+use crate::simplify::{ArcCenter, arc_center};
 use crate::{Command, Point};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -48,7 +53,14 @@ impl BBox {
         }
     }
 
-    fn add_point(&mut self, x: f64, y: f64) {
+    /// Distance from `(x, y)` to the nearest point of the box, zero inside.
+    pub(crate) fn distance_to(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.min_x - x).max(x - self.max_x).max(0.0);
+        let dy = (self.min_y - y).max(y - self.max_y).max(0.0);
+        dx.hypot(dy)
+    }
+
+    pub(crate) fn add_point(&mut self, x: f64, y: f64) {
         if x < self.min_x {
             self.min_x = x;
         }
@@ -64,7 +76,7 @@ impl BBox {
     }
 
     /// Expands the bounding box to enclose a cubic Bezier segment.
-    fn add_cubic(&mut self, start: Point, cp1: Point, cp2: Point, end: Point) {
+    pub(crate) fn add_cubic(&mut self, start: Point, cp1: Point, cp2: Point, end: Point) {
         // Always include the start and end points
         self.add_point(start.x, start.y);
         self.add_point(end.x, end.y);
@@ -74,62 +86,120 @@ impl BBox {
         self.add_bezier_extrema(start.y, cp1.y, cp2.y, end.y, false);
     }
 
-    fn add_bezier_extrema(&mut self, p0: f64, p1: f64, p2: f64, p3: f64, is_x: bool) {
-        // Derivative of cubic Bezier: at^2 + bt + c = 0
-        let a = 3.0 * (-p0 + 3.0 * p1 - 3.0 * p2 + p3);
-        let b = 6.0 * (p0 - 2.0 * p1 + p2);
-        let c = 3.0 * (p1 - p0);
-
-        let find_roots = |a: f64, b: f64, c: f64| {
-            let mut roots = Vec::new();
-            if a.abs() < 1e-9 {
-                // Quadratic reduces to linear
-                if b.abs() > 1e-9 {
-                    roots.push(-c / b);
-                }
-            } else {
-                let discriminant = b * b - 4.0 * a * c;
-                if discriminant >= 0.0 {
-                    let sqrt_d = discriminant.sqrt();
-                    roots.push((-b + sqrt_d) / (2.0 * a));
-                    roots.push((-b - sqrt_d) / (2.0 * a));
-                }
+    fn add_curve(&mut self, start: Point, cp1: Point, cp2: Point, end: Point, loose: bool) {
+        if loose {
+            for p in [start, cp1, cp2, end] {
+                self.add_point(p.x, p.y);
             }
-            roots
-        };
+        } else {
+            self.add_cubic(start, cp1, cp2, end);
+        }
+    }
+
+    /// Expands the bounding box to enclose a quadratic Bezier segment.
+    fn add_quadratic(&mut self, start: Point, ctrl: Point, end: Point, loose: bool) {
+        self.add_point(start.x, start.y);
+        self.add_point(end.x, end.y);
+        if loose {
+            self.add_point(ctrl.x, ctrl.y);
+            return;
+        }
 
-        for t in find_roots(a, b, c) {
-            if t > 0.0 && t < 1.0 {
+        // The derivative is linear, zero at most once per axis
+        let extremum = |p0: f64, p1: f64, p2: f64| {
+            let denom = p0 - 2.0 * p1 + p2;
+            let t = (p0 - p1) / denom;
+            (denom != 0.0 && t > 0.0 && t < 1.0).then(|| {
                 let mt = 1.0 - t;
-                let val = mt * mt * mt * p0
-                    + 3.0 * mt * mt * t * p1
-                    + 3.0 * mt * t * t * p2
-                    + t * t * t * p3;
-                if is_x {
-                    self.min_x = self.min_x.min(val);
-                    self.max_x = self.max_x.max(val);
-                } else {
-                    self.min_y = self.min_y.min(val);
-                    self.max_y = self.max_y.max(val);
-                }
+                mt * mt * p0 + 2.0 * mt * t * p1 + t * t * p2
+            })
+        };
+        if let Some(x) = extremum(start.x, ctrl.x, end.x) {
+            self.add_point(x, start.y);
+        }
+        if let Some(y) = extremum(start.y, ctrl.y, end.y) {
+            self.add_point(start.x, y);
+        }
+    }
+
+    /// Expands the bounding box to enclose an elliptical arc, or its whole
+    /// ellipse when `loose`.
+    fn add_arc(&mut self, arc: &ArcCenter, loose: bool) {
+        let (sin_phi, cos_phi) = arc.phi.sin_cos();
+        // Angles where x and y of the ellipse are at their extremes
+        let ax = (-arc.ry * sin_phi).atan2(arc.rx * cos_phi);
+        let ay = (arc.ry * cos_phi).atan2(arc.rx * sin_phi);
+
+        let (lo, hi) = if loose {
+            (-2.0 * PI, 2.0 * PI)
+        } else if arc.delta < 0.0 {
+            (arc.theta + arc.delta, arc.theta)
+        } else {
+            (arc.theta, arc.theta + arc.delta)
+        };
+        // The end points are already in, skip extremes that round past them
+        let (lo, hi) = (lo + 1e-9, hi - 1e-9);
+        for base in [ax, ay] {
+            let first = ((lo - base) / PI).ceil() as i64;
+            let last = ((hi - base) / PI).floor() as i64;
+            for k in first..=last {
+                let p = arc.point_at(base + k as f64 * PI);
+                self.add_point(p.x, p.y);
+            }
+        }
+    }
+
+    fn add_bezier_extrema(&mut self, p0: f64, p1: f64, p2: f64, p3: f64, is_x: bool) {
+        for t in roots::cubic_extrema(p0, p1, p2, p3) {
+            let val = cubic_at(p0, p1, p2, p3, t);
+            if is_x {
+                self.min_x = self.min_x.min(val);
+                self.max_x = self.max_x.max(val);
+            } else {
+                self.min_y = self.min_y.min(val);
+                self.max_y = self.max_y.max(val);
             }
         }
     }
 }
 
-pub(crate) fn bbox(commands: &[Command]) -> Option<BBox> {
+/// Bounding box of absolute `commands`, `None` when there are none.
+///
+/// A `loose` box encloses the control points and the full ellipse of arcs
+/// in place of the curves themselves, it is cheaper but may be larger.
+pub(crate) fn bbox(commands: &[Command], loose: bool) -> Option<BBox> {
     if commands.is_empty() {
         return None;
     }
 
     let mut bounds = BBox::new();
     let mut cursor = Point { x: 0.0, y: 0.0 };
+    let mut start = cursor;
+    let mut last_cubic = None;
+    let mut last_quad = None;
 
     for cmd in commands {
-        match *cmd {
-            Command::Move { x, y } | Command::Line { x, y } => {
+        let (cubic, quad) = match *cmd {
+            Command::Move { x, y } => {
+                bounds.add_point(x, y);
+                start = Point { x, y };
+                cursor = start;
+                (None, None)
+            }
+            Command::Line { x, y } => {
                 bounds.add_point(x, y);
                 cursor = Point { x, y };
+                (None, None)
+            }
+            Command::Horizontal { x } => {
+                cursor.x = x;
+                bounds.add_point(cursor.x, cursor.y);
+                (None, None)
+            }
+            Command::Vertical { y } => {
+                cursor.y = y;
+                bounds.add_point(cursor.x, cursor.y);
+                (None, None)
             }
             Command::Cubic {
                 x1,
@@ -139,16 +209,67 @@ pub(crate) fn bbox(commands: &[Command]) -> Option<BBox> {
                 x,
                 y,
             } => {
-                let cp1 = Point { x: x1, y: y1 };
-                let cp2 = Point { x: x2, y: y2 };
+                let ctrl2 = Point { x: x2, y: y2 };
                 let end = Point { x, y };
-
-                bounds.add_cubic(cursor, cp1, cp2, end);
+                bounds.add_curve(cursor, Point { x: x1, y: y1 }, ctrl2, end, loose);
                 cursor = end;
+                (Some(ctrl2), None)
             }
-            Command::Close => {}
-            _ => {}
-        }
+            Command::SmoothCubic { x2, y2, x, y } => {
+                let ctrl1 = reflect(last_cubic, cursor);
+                let ctrl2 = Point { x: x2, y: y2 };
+                let end = Point { x, y };
+                bounds.add_curve(cursor, ctrl1, ctrl2, end, loose);
+                cursor = end;
+                (Some(ctrl2), None)
+            }
+            Command::Quadratic { x1, y1, x, y } => {
+                let ctrl = Point { x: x1, y: y1 };
+                let end = Point { x, y };
+                bounds.add_quadratic(cursor, ctrl, end, loose);
+                cursor = end;
+                (None, Some(ctrl))
+            }
+            Command::SmoothQuadratic { x, y } => {
+                let ctrl = reflect(last_quad, cursor);
+                let end = Point { x, y };
+                bounds.add_quadratic(cursor, ctrl, end, loose);
+                cursor = end;
+                (None, Some(ctrl))
+            }
+            Command::Arc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc_flag,
+                sweep_flag,
+                x,
+                y,
+            } => {
+                let end = Point { x, y };
+                bounds.add_point(x, y);
+                let arc = arc_center(
+                    cursor,
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc_flag,
+                    sweep_flag,
+                    end,
+                );
+                if let Some(arc) = arc {
+                    bounds.add_arc(&arc, loose);
+                }
+                cursor = end;
+                (None, None)
+            }
+            Command::Close => {
+                cursor = start;
+                (None, None)
+            }
+        };
+        last_cubic = cubic;
+        last_quad = quad;
     }
 
     if bounds.min_x == f64::INFINITY {
@@ -158,6 +279,16 @@ pub(crate) fn bbox(commands: &[Command]) -> Option<BBox> {
     }
 }
 
+fn reflect(p: Option<Point>, center: Point) -> Point {
+    match p {
+        Some(p) => Point {
+            x: 2.0 * center.x - p.x,
+            y: 2.0 * center.y - p.y,
+        },
+        None => center,
+    }
+}
+
 #[cfg(test)]
 mod t {
     use super::*;
@@ -170,7 +301,7 @@ mod t {
             Line { x: 37.0, y: 10.0 },
             Line { x: 29.0, y: 134.0 },
         ];
-        let bb = bbox(&p);
+        let bb = bbox(&p, false);
         assert!(bb.is_some());
         let bb = bb.unwrap();
         assert_eq!(bb, BBox::init(15.0, 10.0, 37.0, 134.0));
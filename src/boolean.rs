@@ -0,0 +1,297 @@
+use crate::hit::{self, FillRule};
+use crate::intersect::{self, MERGE_DISTANCE};
+use crate::segment::{self, Segment};
+use crate::{BBox, Command, Point};
+
+/// How two filled paths are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Area covered by either path
+    Union,
+    /// Area covered by both paths
+    Intersection,
+    /// Area covered by the first path but not the second
+    Difference,
+    /// Area covered by exactly one of the paths
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Xor => a != b,
+        }
+    }
+}
+
+fn near(p: Point, q: Point) -> bool {
+    (p.x - q.x).hypot(p.y - q.y) < MERGE_DISTANCE
+}
+
+/// Copy of `seg` with its end points moved to `from` and `to`.
+fn with_ends(seg: Segment, from: Point, to: Point) -> Segment {
+    match seg {
+        Segment::Line { .. } => Segment::Line { from, to },
+        Segment::Cubic { ctrl1, ctrl2, .. } => Segment::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        },
+    }
+}
+
+fn reversed(seg: Segment) -> Segment {
+    match seg {
+        Segment::Line { from, to } => Segment::Line { from: to, to: from },
+        Segment::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => Segment::Cubic {
+            from: to,
+            ctrl1: ctrl2,
+            ctrl2: ctrl1,
+            to: from,
+        },
+    }
+}
+
+/// Segments of all subpaths closed, without empty ones.
+fn closed_segments(commands: &[Command]) -> Vec<Segment> {
+    let mut segs = Vec::new();
+    segment::for_each_closed(commands, |seg| {
+        let empty = match *seg {
+            Segment::Line { from, to } => from == to,
+            Segment::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => from == ctrl1 && from == ctrl2 && from == to,
+        };
+        if !empty {
+            segs.push(*seg);
+        }
+    });
+    segs
+}
+
+/// Parameters on line `a` where the ends of a collinear line `b` lie.
+fn collinear_splits(a: &Segment, b: &Segment) -> Vec<(f64, Point)> {
+    let (Segment::Line { from, to }, Segment::Line { from: q0, to: q1 }) = (*a, *b) else {
+        return Vec::new();
+    };
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let len2 = dx * dx + dy * dy;
+    let len = len2.sqrt();
+    let on_line =
+        |p: Point| ((p.x - from.x) * dy - (p.y - from.y) * dx).abs() / len < MERGE_DISTANCE;
+    if !(on_line(q0) && on_line(q1)) {
+        return Vec::new();
+    }
+    [q0, q1]
+        .into_iter()
+        .filter_map(|p| {
+            let t = ((p.x - from.x) * dx + (p.y - from.y) * dy) / len2;
+            (t > 0.0 && t < 1.0).then_some((t, p))
+        })
+        .collect()
+}
+
+/// Cut every segment where it crosses or touches another one.
+fn split_edges(segs: &[Segment]) -> Vec<Segment> {
+    let mut splits: Vec<Vec<(f64, Point)>> = vec![Vec::new(); segs.len()];
+    for (i, a) in segs.iter().enumerate() {
+        for (t, u) in intersect::cubic_self(a) {
+            splits[i].push((t, a.point_at(t)));
+            splits[i].push((u, a.point_at(t)));
+        }
+        for (j, b) in segs.iter().enumerate().skip(i + 1) {
+            for (t, u) in intersect::segment_pair(a, b) {
+                let p = a.point_at(t);
+                splits[i].push((t, p));
+                splits[j].push((u, p));
+            }
+            let ab = collinear_splits(a, b);
+            let ba = collinear_splits(b, a);
+            splits[i].extend(ab);
+            splits[j].extend(ba);
+        }
+    }
+
+    let mut edges = Vec::new();
+    for (seg, mut cuts) in segs.iter().zip(splits) {
+        cuts.retain(|(_, p)| !near(*p, seg.from()) && !near(*p, seg.to()));
+        cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        cuts.dedup_by(|a, b| near(a.1, b.1));
+
+        let mut t0 = 0.0;
+        let mut p0 = seg.from();
+        for (t1, p1) in cuts.into_iter().chain([(1.0, seg.to())]) {
+            edges.push(with_ends(seg.subsegment(t0, t1), p0, p1));
+            (t0, p0) = (t1, p1);
+        }
+    }
+    edges
+}
+
+pub(crate) fn boolean(
+    a: &[Command],
+    b: &[Command],
+    op: BooleanOp,
+    rule_a: FillRule,
+    rule_b: FillRule,
+) -> Vec<Command> {
+    let segs_a = closed_segments(a);
+    let segs_b = closed_segments(b);
+
+    let mut bounds = BBox::new();
+    for seg in segs_a.iter().chain(&segs_b) {
+        let bb = seg.bbox();
+        bounds.add_point(bb.min_x, bb.min_y);
+        bounds.add_point(bb.max_x, bb.max_y);
+    }
+    let size = bounds.width().max(bounds.height());
+    if !size.is_finite() || size <= 0.0 {
+        return Vec::new();
+    }
+    // Distance of the probe points from an edge
+    let eps = size * 1e-7;
+
+    let inside = |p: Point| {
+        op.apply(
+            hit::contains(a, p.x, p.y, rule_a),
+            hit::contains(b, p.x, p.y, rule_b),
+        )
+    };
+
+    // Keep edges separating the result from the rest, with the result on
+    // the side of their normal
+    let all: Vec<_> = segs_a.iter().chain(&segs_b).copied().collect();
+    let mut kept: Vec<Segment> = Vec::new();
+    for edge in split_edges(&all) {
+        let m = edge.point_at(0.5);
+        let Some(n) = edge.normal_at(0.5) else {
+            continue;
+        };
+        let left = inside(Point {
+            x: m.x + n.x * eps,
+            y: m.y + n.y * eps,
+        });
+        let right = inside(Point {
+            x: m.x - n.x * eps,
+            y: m.y - n.y * eps,
+        });
+        if left == right {
+            continue;
+        }
+        let edge = if left { edge } else { reversed(edge) };
+
+        // Shared boundaries of both paths show up twice
+        let duplicate = kept.iter().any(|k| {
+            near(k.from(), edge.from()) && near(k.to(), edge.to()) && near(k.point_at(0.5), m)
+        });
+        if !duplicate {
+            kept.push(edge);
+        }
+    }
+
+    link(kept)
+}
+
+/// `b` continues `a` in the same direction along one straight line.
+fn continues_line(a: &Segment, b: &Segment) -> bool {
+    let (Segment::Line { from, to: mid }, Segment::Line { to, .. }) = (*a, *b) else {
+        return false;
+    };
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let len = dx.hypot(dy);
+    let forward = (mid.x - from.x) * dx + (mid.y - from.y) * dy > 0.0
+        && (to.x - mid.x) * dx + (to.y - mid.y) * dy > 0.0;
+    forward && ((mid.x - from.x) * dy - (mid.y - from.y) * dx).abs() / len < MERGE_DISTANCE
+}
+
+/// Join consecutive collinear lines, over the start point too if `closed`.
+fn merge_collinear(contour: &mut Vec<Segment>, closed: bool) {
+    let mut i = 0;
+    while contour.len() > 2 && i < contour.len() {
+        let j = (i + 1) % contour.len();
+        if (j > 0 || closed) && continues_line(&contour[i], &contour[j]) {
+            contour[i] = Segment::Line {
+                from: contour[i].from(),
+                to: contour[j].to(),
+            };
+            contour.remove(j);
+            // The merged line may continue the one before it
+            i = i.saturating_sub(1).min(contour.len() - 1);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Chain oriented edges into closed contours.
+fn link(edges: Vec<Segment>) -> Vec<Command> {
+    let mut used = vec![false; edges.len()];
+    let mut commands = Vec::new();
+
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        let start = edges[first].from();
+        let mut contour = Vec::new();
+        let mut closed = false;
+
+        let mut current = first;
+        loop {
+            used[current] = true;
+            let edge = edges[current];
+            if near(edge.to(), start) {
+                contour.push(with_ends(edge, edge.from(), start));
+                closed = true;
+                break;
+            }
+            contour.push(edge);
+            // Where contours touch, turn hardest toward the inside to keep them apart
+            let incoming = edge.tangent_at(1.0);
+            let turn = |i: &usize| {
+                let (Some(d0), Some(d1)) = (incoming, edges[*i].tangent_at(0.0)) else {
+                    return 0.0;
+                };
+                (d0.x * d1.y - d0.y * d1.x).atan2(d0.x * d1.x + d0.y * d1.y)
+            };
+            let next = (0..edges.len())
+                .filter(|&i| !used[i] && near(edges[i].from(), edge.to()))
+                .max_by(|a, b| turn(a).total_cmp(&turn(b)));
+            match next {
+                Some(next) => current = next,
+                // Only left over when numeric noise broke the contour
+                None => break,
+            }
+        }
+
+        merge_collinear(&mut contour, closed);
+        let start = contour[0].from();
+        commands.push(Command::Move {
+            x: start.x,
+            y: start.y,
+        });
+        let last = contour.len() - 1;
+        for (i, seg) in contour.into_iter().enumerate() {
+            // The closepath draws the last line
+            if !(closed && i == last && matches!(seg, Segment::Line { .. })) {
+                commands.push(seg.to_command());
+            }
+        }
+        if closed {
+            commands.push(Command::Close);
+        }
+    }
+    commands
+}
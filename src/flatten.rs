@@ -0,0 +1,217 @@
+use crate::segment::Segment;
+use crate::{Command, Point};
+
+/// Points of a flattened subpath.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    /// Closed polylines do not repeat the first point at the end.
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+/// Panic on tolerances that would give no or endless subdivision.
+pub(crate) fn check_tolerance(tolerance: f64) {
+    assert!(
+        tolerance.is_finite() && tolerance > 0.0,
+        "flatten tolerance must be positive and finite, got {tolerance}"
+    );
+}
+
+/// Number of lines needed to stay within `tolerance` of a cubic (Wang's formula).
+fn wang_steps(from: Point, ctrl1: Point, ctrl2: Point, to: Point, tolerance: f64) -> usize {
+    let dd = |a: Point, b: Point, c: Point| (a.x - 2.0 * b.x + c.x).hypot(a.y - 2.0 * b.y + c.y);
+    let m = dd(from, ctrl1, ctrl2).max(dd(ctrl1, ctrl2, to));
+    let n = (0.75 * m / tolerance.max(1e-12)).sqrt().ceil();
+    (n as usize).clamp(1, 1 << 16)
+}
+
+/// Points along `seg`, not including its start point, deviating at most `tolerance`.
+pub(crate) fn flatten_segment(seg: &Segment, tolerance: f64, out: &mut Vec<Point>) {
+    match *seg {
+        Segment::Line { to, .. } => out.push(to),
+        Segment::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => {
+            let n = wang_steps(from, ctrl1, ctrl2, to, tolerance);
+            for i in 1..n {
+                out.push(seg.point_at(i as f64 / n as f64));
+            }
+            out.push(to);
+        }
+    }
+}
+
+pub(crate) fn flatten(commands: &[Command], tolerance: f64) -> Vec<Command> {
+    let mut result = Vec::with_capacity(commands.len());
+    let mut points = Vec::new();
+    let mut cursor = Point { x: 0.0, y: 0.0 };
+    let mut start = cursor;
+
+    for cmd in commands {
+        match *cmd {
+            Command::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let seg = Segment::Cubic {
+                    from: cursor,
+                    ctrl1: Point { x: x1, y: y1 },
+                    ctrl2: Point { x: x2, y: y2 },
+                    to: Point { x, y },
+                };
+                points.clear();
+                flatten_segment(&seg, tolerance, &mut points);
+                result.extend(points.iter().map(|p| Command::Line { x: p.x, y: p.y }));
+            }
+            _ => result.push(cmd.clone()),
+        }
+        cursor = match *cmd {
+            Command::Move { x, y } => {
+                start = Point { x, y };
+                start
+            }
+            Command::Line { x, y } | Command::Cubic { x, y, .. } => Point { x, y },
+            Command::Close => start,
+            _ => cursor,
+        };
+    }
+    result
+}
+
+/// Iterator over the flattened subpaths of a `SimplePath`,
+/// created by [`SimplePath::polylines`](crate::SimplePath::polylines).
+pub struct Polylines<'a> {
+    commands: &'a [Command],
+    index: usize,
+    tolerance: f64,
+    cursor: Point,
+    start: Point,
+}
+
+impl<'a> Polylines<'a> {
+    pub(crate) fn new(commands: &'a [Command], tolerance: f64) -> Self {
+        Self {
+            commands,
+            index: 0,
+            tolerance,
+            cursor: Point { x: 0.0, y: 0.0 },
+            start: Point { x: 0.0, y: 0.0 },
+        }
+    }
+}
+
+impl Iterator for Polylines<'_> {
+    type Item = Polyline;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut points = Vec::new();
+
+        while let Some(cmd) = self.commands.get(self.index) {
+            let from = self.cursor;
+            let seg = match *cmd {
+                Command::Move { x, y } => {
+                    // Leave the move for the next call
+                    if points.len() > 1 {
+                        break;
+                    }
+                    self.index += 1;
+                    self.cursor = Point { x, y };
+                    self.start = self.cursor;
+                    points.clear();
+                    points.push(self.cursor);
+                    continue;
+                }
+                Command::Line { x, y } => Segment::Line {
+                    from,
+                    to: Point { x, y },
+                },
+                Command::Cubic {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => Segment::Cubic {
+                    from,
+                    ctrl1: Point { x: x1, y: y1 },
+                    ctrl2: Point { x: x2, y: y2 },
+                    to: Point { x, y },
+                },
+                Command::Close => {
+                    self.index += 1;
+                    if points.is_empty() {
+                        points.push(from);
+                    }
+                    self.cursor = self.start;
+                    return Some(Polyline {
+                        points,
+                        closed: true,
+                    });
+                }
+                _ => {
+                    self.index += 1;
+                    continue;
+                }
+            };
+
+            self.index += 1;
+            if points.is_empty() {
+                points.push(from);
+            }
+            flatten_segment(&seg, self.tolerance, &mut points);
+            self.cursor = seg.to();
+        }
+
+        if points.len() > 1 {
+            Some(Polyline {
+                points,
+                closed: false,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn max_deviation() {
+        let p = |x, y| Point { x, y };
+        let seg = Segment::Cubic {
+            from: p(0.0, 0.0),
+            ctrl1: p(0.0, 100.0),
+            ctrl2: p(100.0, 100.0),
+            to: p(100.0, 0.0),
+        };
+
+        for tolerance in [1.0, 0.1, 0.01] {
+            let mut points = vec![seg.from()];
+            flatten_segment(&seg, tolerance, &mut points);
+
+            // Compare densely sampled curve points with the polyline
+            let n = points.len() - 1;
+            for i in 0..=1000 {
+                let t = i as f64 / 1000.0;
+                let q = seg.point_at(t);
+                let k = ((t * n as f64) as usize).min(n - 1);
+                let (a, b) = (points[k], points[k + 1]);
+                let (dx, dy) = (b.x - a.x, b.y - a.y);
+                let u =
+                    (((q.x - a.x) * dx + (q.y - a.y) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+                let dist = (a.x + u * dx - q.x).hypot(a.y + u * dy - q.y);
+                assert!(dist <= tolerance);
+            }
+        }
+    }
+}
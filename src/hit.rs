@@ -0,0 +1,159 @@
+use crate::roots;
+use crate::segment::{self, Segment};
+use crate::{Command, Point};
+
+/// Rule deciding which points are inside a path, like the SVG `fill-rule` property.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside if the winding number is not zero
+    #[default]
+    NonZero,
+    /// Inside if a ray from the point crosses the outline an odd number of times
+    EvenOdd,
+}
+
+/// Winding number of the outline around `(x, y)`, open subpaths are closed.
+pub(crate) fn winding(commands: &[Command], x: f64, y: f64) -> i32 {
+    let mut w = 0;
+    segment::for_each_closed(commands, |seg| w += segment_winding(seg, x, y));
+    w
+}
+
+pub(crate) fn contains(commands: &[Command], x: f64, y: f64, rule: FillRule) -> bool {
+    let w = winding(commands, x, y);
+    match rule {
+        FillRule::NonZero => w != 0,
+        FillRule::EvenOdd => w % 2 != 0,
+    }
+}
+
+/// Crossings of a ray from `(px, py)` toward positive x with `seg`.
+fn segment_winding(seg: &Segment, px: f64, py: f64) -> i32 {
+    match *seg {
+        Segment::Line { .. } => crossing(seg, 0.0, 1.0, px, py),
+        Segment::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => {
+            let ys = [from.y, ctrl1.y, ctrl2.y, to.y];
+            let xs = [from.x, ctrl1.x, ctrl2.x, to.x];
+            if ys.iter().all(|y| *y > py)
+                || ys.iter().all(|y| *y < py)
+                || xs.iter().all(|x| *x <= px)
+            {
+                return 0;
+            }
+
+            // Count each y-monotone piece on its own
+            let mut w = 0;
+            let mut t0 = 0.0;
+            for t1 in roots::cubic_extrema(from.y, ctrl1.y, ctrl2.y, to.y)
+                .into_iter()
+                .chain([1.0])
+            {
+                w += crossing(seg, t0, t1, px, py);
+                t0 = t1;
+            }
+            w
+        }
+    }
+}
+
+/// Crossing with the y-monotone part of `seg` between `t0` and `t1`.
+///
+/// Pieces are treated as half-open in y, so a ray through a vertex shared by
+/// two pieces is counted once.
+fn crossing(seg: &Segment, t0: f64, t1: f64, px: f64, py: f64) -> i32 {
+    let y0 = seg.point_at(t0).y;
+    let y1 = seg.point_at(t1).y;
+    let dir = if y0 <= py && py < y1 {
+        1
+    } else if y1 <= py && py < y0 {
+        -1
+    } else {
+        return 0;
+    };
+    let t = roots::bisect(|t| seg.point_at(t).y - py, t0, t1);
+    if seg.point_at(t).x > px { dir } else { 0 }
+}
+
+/// Point on a path closest to a query point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearestPoint {
+    pub point: Point,
+    /// Index of the command drawing the segment
+    pub index: usize,
+    /// Parameter on the segment in `[0, 1]`
+    pub t: f64,
+    pub distance: f64,
+}
+
+/// Closest point on any segment within `max_distance` of `(x, y)`.
+pub(crate) fn nearest(
+    commands: &[Command],
+    x: f64,
+    y: f64,
+    max_distance: f64,
+) -> Option<NearestPoint> {
+    let mut best: Option<NearestPoint> = None;
+    for (index, seg) in segment::segments(commands) {
+        let limit = best.map_or(max_distance, |b| b.distance);
+        // The segment lies inside its box, so nothing in it can be closer
+        if seg.bbox().distance_to(x, y) > limit {
+            continue;
+        }
+        let t = nearest_t(&seg, x, y);
+        let point = seg.point_at(t);
+        let distance = (point.x - x).hypot(point.y - y);
+        if distance <= limit && best.is_none_or(|b| distance < b.distance) {
+            best = Some(NearestPoint {
+                point,
+                index,
+                t,
+                distance,
+            });
+        }
+    }
+    best
+}
+
+/// Parameter of the point on `seg` closest to `(px, py)`.
+pub(crate) fn nearest_t(seg: &Segment, px: f64, py: f64) -> f64 {
+    let dist2 = |t: f64| {
+        let p = seg.point_at(t);
+        (p.x - px).powi(2) + (p.y - py).powi(2)
+    };
+    match *seg {
+        Segment::Line { from, to } => {
+            let (dx, dy) = (to.x - from.x, to.y - from.y);
+            let len2 = dx * dx + dy * dy;
+            if len2 == 0.0 {
+                return 0.0;
+            }
+            (((px - from.x) * dx + (py - from.y) * dy) / len2).clamp(0.0, 1.0)
+        }
+        Segment::Cubic { .. } => {
+            // (B(t) - P) . B'(t) = 0 is a quintic in power form
+            let (mut qx, mut qy) = seg.power_coeffs();
+            qx[0] -= px;
+            qy[0] -= py;
+            let mut coeffs = [0.0; 6];
+            for q in [qx, qy] {
+                let d = [q[1], 2.0 * q[2], 3.0 * q[3]];
+                for (i, a) in q.iter().enumerate() {
+                    for (j, b) in d.iter().enumerate() {
+                        coeffs[i + j] += a * b;
+                    }
+                }
+            }
+
+            roots::polynomial(&coeffs, 0.0, 1.0)
+                .into_iter()
+                .chain([0.0, 1.0])
+                .min_by(|a, b| dist2(*a).total_cmp(&dist2(*b)))
+                .unwrap_or(0.0)
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use crate::roots;
+use crate::segment::{self, Segment};
+use crate::{BBox, Command, Point};
+
+// Parameter slack accepted at segment ends
+const EPSILON: f64 = 1e-9;
+// Cubics flatter than this are intersected as their chords
+const FLATNESS: f64 = 1e-7;
+// Intersections closer than this are the same point
+pub(crate) const MERGE_DISTANCE: f64 = 1e-6;
+
+/// Crossing of two segments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection {
+    pub point: Point,
+    /// Index of the command drawing the segment in the first path
+    pub index_a: usize,
+    /// Parameter on that segment in `[0, 1]`
+    pub t_a: f64,
+    /// Index of the command drawing the segment in the second path
+    pub index_b: usize,
+    /// Parameter on that segment in `[0, 1]`
+    pub t_b: f64,
+}
+
+fn overlaps(a: &BBox, b: &BBox) -> bool {
+    a.min_x <= b.max_x + EPSILON
+        && b.min_x <= a.max_x + EPSILON
+        && a.min_y <= b.max_y + EPSILON
+        && b.min_y <= a.max_y + EPSILON
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    Point {
+        x: a.x - b.x,
+        y: a.y - b.y,
+    }
+}
+
+/// Parameters on `a` and `b` where the segments cross.
+///
+/// Collinear overlapping parts are not reported.
+pub(crate) fn segment_pair(a: &Segment, b: &Segment) -> Vec<(f64, f64)> {
+    if !overlaps(&a.bbox(), &b.bbox()) {
+        return Vec::new();
+    }
+    let mut found = match (a, b) {
+        (Segment::Line { from, to }, Segment::Line { from: q0, to: q1 }) => {
+            line_line(*from, *to, *q0, *q1).into_iter().collect()
+        }
+        (Segment::Line { from, to }, Segment::Cubic { .. }) => line_cubic(*from, *to, b),
+        (Segment::Cubic { .. }, Segment::Line { from, to }) => line_cubic(*from, *to, a)
+            .into_iter()
+            .map(|(u, t)| (t, u))
+            .collect(),
+        (Segment::Cubic { .. }, Segment::Cubic { .. }) => {
+            let mut found = Vec::new();
+            cubic_cubic(a, (0.0, 1.0), b, (0.0, 1.0), 40, &mut found);
+            found.into_iter().map(|(t, u)| refine(a, b, t, u)).collect()
+        }
+    };
+
+    found.sort_by(|x, y| x.0.total_cmp(&y.0));
+    found.dedup_by(|x, y| {
+        let (p, q) = (a.point_at(x.0), a.point_at(y.0));
+        (p.x - q.x).hypot(p.y - q.y) < MERGE_DISTANCE
+    });
+    found
+}
+
+fn line_line(p0: Point, p1: Point, q0: Point, q1: Point) -> Option<(f64, f64)> {
+    let r = sub(p1, p0);
+    let s = sub(q1, q0);
+    let denom = cross(r, s);
+    if denom.abs() <= 1e-12 * r.x.hypot(r.y) * s.x.hypot(s.y) {
+        // Parallel or degenerate
+        return None;
+    }
+    let qp = sub(q0, p0);
+    let t = cross(qp, s) / denom;
+    let u = cross(qp, r) / denom;
+    let range = -EPSILON..=1.0 + EPSILON;
+    if range.contains(&t) && range.contains(&u) {
+        Some((t.clamp(0.0, 1.0), u.clamp(0.0, 1.0)))
+    } else {
+        None
+    }
+}
+
+fn line_cubic(p0: Point, p1: Point, cubic: &Segment) -> Vec<(f64, f64)> {
+    let d = sub(p1, p0);
+    let len2 = d.x * d.x + d.y * d.y;
+    if len2 == 0.0 {
+        return Vec::new();
+    }
+
+    // Signed distance of the cubic from the line is a cubic polynomial
+    let (cx, cy) = cubic.power_coeffs();
+    let mut coeffs = [0.0; 4];
+    for i in 0..4 {
+        coeffs[i] = cx[i] * d.y - cy[i] * d.x;
+    }
+    coeffs[0] -= p0.x * d.y - p0.y * d.x;
+
+    roots::polynomial(&coeffs, 0.0, 1.0)
+        .into_iter()
+        .filter_map(|u| {
+            let q = sub(cubic.point_at(u), p0);
+            let t = (q.x * d.x + q.y * d.y) / len2;
+            (-EPSILON..=1.0 + EPSILON)
+                .contains(&t)
+                .then(|| (t.clamp(0.0, 1.0), u))
+        })
+        .collect()
+}
+
+/// Distance of the control points from the chord.
+fn flatness(seg: &Segment) -> f64 {
+    let Segment::Cubic {
+        from,
+        ctrl1,
+        ctrl2,
+        to,
+    } = *seg
+    else {
+        return 0.0;
+    };
+    let d = sub(to, from);
+    let len = d.x.hypot(d.y);
+    let dist = |p: Point| {
+        let v = sub(p, from);
+        if len == 0.0 {
+            v.x.hypot(v.y)
+        } else {
+            cross(d, v).abs() / len
+        }
+    };
+    dist(ctrl1).max(dist(ctrl2))
+}
+
+/// Check if both cubics have the same control points, in either direction.
+fn same_curve(a: &Segment, b: &Segment) -> bool {
+    let (
+        Segment::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        },
+        Segment::Cubic {
+            from: b0,
+            ctrl1: b1,
+            ctrl2: b2,
+            to: b3,
+        },
+    ) = (*a, *b)
+    else {
+        return false;
+    };
+    let near = |p: Point, q: Point| (p.x - q.x).hypot(p.y - q.y) < MERGE_DISTANCE;
+    let pa = [from, ctrl1, ctrl2, to];
+    let forward = [b0, b1, b2, b3];
+    let backward = [b3, b2, b1, b0];
+    pa.iter().zip(forward).all(|(p, q)| near(*p, q))
+        || pa.iter().zip(backward).all(|(p, q)| near(*p, q))
+}
+
+/// Check if the chord of `b` lies on the line through the chord of `a`.
+///
+/// Neighbouring flat pieces of one curve deviate by about 8 times the
+/// flatness limit, which is still counted as collinear.
+fn collinear(a: &Segment, b: &Segment) -> bool {
+    let chord = Segment::Cubic {
+        from: a.from(),
+        ctrl1: b.from(),
+        ctrl2: b.to(),
+        to: a.to(),
+    };
+    flatness(&chord) < 16.0 * FLATNESS
+}
+
+/// Subdivide both cubics while their bounding boxes overlap.
+fn cubic_cubic(
+    a: &Segment,
+    ra: (f64, f64),
+    b: &Segment,
+    rb: (f64, f64),
+    depth: u32,
+    out: &mut Vec<(f64, f64)>,
+) {
+    if !overlaps(&a.bbox(), &b.bbox()) || same_curve(a, b) {
+        return;
+    }
+    if depth == 0 || (flatness(a) < FLATNESS && flatness(b) < FLATNESS) {
+        // Overlapping parts of coincident curves are not crossings
+        if collinear(a, b) && collinear(b, a) {
+            return;
+        }
+        if let Some((t, u)) = line_line(a.from(), a.to(), b.from(), b.to()) {
+            out.push((ra.0 + (ra.1 - ra.0) * t, rb.0 + (rb.1 - rb.0) * u));
+        }
+        return;
+    }
+
+    let (a1, a2) = a.split(0.5);
+    let (b1, b2) = b.split(0.5);
+    let ma = (ra.0 + ra.1) / 2.0;
+    let mb = (rb.0 + rb.1) / 2.0;
+    for (sa, rsa) in [(a1, (ra.0, ma)), (a2, (ma, ra.1))] {
+        for (sb, rsb) in [(b1, (rb.0, mb)), (b2, (mb, rb.1))] {
+            cubic_cubic(&sa, rsa, &sb, rsb, depth - 1, out);
+        }
+    }
+}
+
+/// Polish an approximate crossing with Newton's method on `a(t) - b(u) = 0`.
+fn refine(a: &Segment, b: &Segment, mut t: f64, mut u: f64) -> (f64, f64) {
+    for _ in 0..8 {
+        let f = sub(a.point_at(t), b.point_at(u));
+        let da = a.derivative(t);
+        let db = b.derivative(u);
+        // Jacobian columns are da and -db
+        let det = cross(db, da);
+        if det.abs() < 1e-12 {
+            break;
+        }
+        let dt = cross(db, f) / det;
+        let du = cross(da, f) / det;
+        let (nt, nu) = ((t - dt).clamp(0.0, 1.0), (u - du).clamp(0.0, 1.0));
+        if (nt - t).abs() < 1e-15 && (nu - u).abs() < 1e-15 {
+            break;
+        }
+        (t, u) = (nt, nu);
+    }
+    (t, u)
+}
+
+fn push_unique(out: &mut Vec<Intersection>, x: Intersection) {
+    let close =
+        |y: &Intersection| (x.point.x - y.point.x).hypot(x.point.y - y.point.y) < MERGE_DISTANCE;
+    if !out.iter().any(close) {
+        out.push(x);
+    }
+}
+
+pub(crate) fn intersections(a: &[Command], b: &[Command]) -> Vec<Intersection> {
+    let segs_b: Vec<_> = segment::segments(b).collect();
+    let mut out = Vec::new();
+    for (index_a, sa) in segment::segments(a) {
+        for (index_b, sb) in &segs_b {
+            for (t_a, t_b) in segment_pair(&sa, sb) {
+                let point = sa.point_at(t_a);
+                push_unique(
+                    &mut out,
+                    Intersection {
+                        point,
+                        index_a,
+                        t_a,
+                        index_b: *index_b,
+                        t_b,
+                    },
+                );
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn self_intersections(commands: &[Command]) -> Vec<Intersection> {
+    let segs: Vec<_> = segment::segments(commands).collect();
+    let mut out = Vec::new();
+
+    for (i, (index_a, sa)) in segs.iter().enumerate() {
+        // A single cubic can loop onto itself, its monotone pieces cannot
+        if let Segment::Cubic { .. } = sa {
+            for (t_a, t_b) in cubic_self(sa) {
+                push_unique(
+                    &mut out,
+                    Intersection {
+                        point: sa.point_at(t_a),
+                        index_a: *index_a,
+                        t_a,
+                        index_b: *index_a,
+                        t_b,
+                    },
+                );
+            }
+        }
+
+        for (index_b, sb) in &segs[i + 1..] {
+            for (t_a, t_b) in segment_pair(sa, sb) {
+                // Ends where consecutive segments join are not crossings
+                let point = sa.point_at(t_a);
+                let at = |p: Point| (point.x - p.x).hypot(point.y - p.y) < MERGE_DISTANCE;
+                if (at(sa.to()) && at(sb.from())) || (at(sa.from()) && at(sb.to())) {
+                    continue;
+                }
+                push_unique(
+                    &mut out,
+                    Intersection {
+                        point,
+                        index_a: *index_a,
+                        t_a,
+                        index_b: *index_b,
+                        t_b,
+                    },
+                );
+            }
+        }
+    }
+    out
+}
+
+/// Self crossings of a cubic, found between its x and y monotone pieces.
+pub(crate) fn cubic_self(seg: &Segment) -> Vec<(f64, f64)> {
+    let Segment::Cubic {
+        from,
+        ctrl1,
+        ctrl2,
+        to,
+    } = *seg
+    else {
+        return Vec::new();
+    };
+    let mut ts = roots::cubic_extrema(from.x, ctrl1.x, ctrl2.x, to.x);
+    ts.extend(roots::cubic_extrema(from.y, ctrl1.y, ctrl2.y, to.y));
+    ts.push(0.0);
+    ts.push(1.0);
+    ts.sort_by(f64::total_cmp);
+    ts.dedup();
+
+    let mut found = Vec::new();
+    for i in 0..ts.len() - 1 {
+        // Neighbouring pieces only share their joint
+        for j in i + 2..ts.len() - 1 {
+            let pa = seg.subsegment(ts[i], ts[i + 1]);
+            let pb = seg.subsegment(ts[j], ts[j + 1]);
+            for (t, u) in segment_pair(&pa, &pb) {
+                let t = ts[i] + (ts[i + 1] - ts[i]) * t;
+                let u = ts[j] + (ts[j + 1] - ts[j]) * u;
+                found.push(refine(seg, seg, t, u));
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn segments() {
+        let p = |x, y| Point { x, y };
+        let l1 = Segment::Line {
+            from: p(0.0, 0.0),
+            to: p(10.0, 10.0),
+        };
+        let l2 = Segment::Line {
+            from: p(0.0, 10.0),
+            to: p(10.0, 0.0),
+        };
+        assert_eq!(segment_pair(&l1, &l2), [(0.5, 0.5)]);
+
+        let arch = Segment::Cubic {
+            from: p(0.0, 0.0),
+            ctrl1: p(0.0, 10.0),
+            ctrl2: p(10.0, 10.0),
+            to: p(10.0, 0.0),
+        };
+        let horizontal = Segment::Line {
+            from: p(-5.0, 5.0),
+            to: p(15.0, 5.0),
+        };
+        let hits = segment_pair(&arch, &horizontal);
+        assert_eq!(hits.len(), 2);
+        for (t, u) in hits {
+            let (q, r) = (arch.point_at(t), horizontal.point_at(u));
+            assert!((q.x - r.x).abs() < 1e-9 && (q.y - r.y).abs() < 1e-9);
+        }
+
+        let flipped = Segment::Cubic {
+            from: p(0.0, 7.5),
+            ctrl1: p(0.0, -2.5),
+            ctrl2: p(10.0, -2.5),
+            to: p(10.0, 7.5),
+        };
+        let hits = segment_pair(&arch, &flipped);
+        assert_eq!(hits.len(), 2);
+        for (t, u) in hits {
+            let (q, r) = (arch.point_at(t), flipped.point_at(u));
+            assert!((q.x - r.x).abs() < 1e-12 && (q.y - r.y).abs() < 1e-12);
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use std::iter::Peekable;
-use std::str::Chars;
+use std::error::Error;
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
@@ -7,109 +7,387 @@ pub enum Token {
     Number(f64),
 }
 
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Command(c) => write!(f, "command '{c}'"),
+            Token::Number(n) => write!(f, "number {n}"),
+        }
+    }
+}
+
+/// Location inside the source string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// Byte offset from the start of the input
+    pub offset: usize,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column number in characters, starting at 1
+    pub column: usize,
+}
+
+impl Position {
+    /// Position of the first character of the input.
+    pub(crate) const START: Position = Position {
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, col {}", self.line, self.column)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum LexerError {
-    UnexpectedCharacter(char),
-    InvalidCommand(char),
-    InvalidNumber(String),
+    UnexpectedCharacter {
+        ch: char,
+        pos: Position,
+    },
+    InvalidCommand {
+        ch: char,
+        pos: Position,
+    },
+    InvalidNumber {
+        text: String,
+        pos: Position,
+    },
+    /// Arc flags must be exactly `0` or `1`
+    InvalidFlag {
+        ch: char,
+        pos: Position,
+    },
 }
 
+impl LexerError {
+    /// Where in the input this error occurred.
+    pub fn position(&self) -> Position {
+        match self {
+            LexerError::UnexpectedCharacter { pos, .. }
+            | LexerError::InvalidCommand { pos, .. }
+            | LexerError::InvalidNumber { pos, .. }
+            | LexerError::InvalidFlag { pos, .. } => *pos,
+        }
+    }
+}
+
+impl fmt::Display for LexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexerError::UnexpectedCharacter { ch, pos } => {
+                write!(f, "{pos}: unexpected character '{ch}'")
+            }
+            LexerError::InvalidCommand { ch, pos } => {
+                write!(f, "{pos}: invalid command '{ch}'")
+            }
+            LexerError::InvalidNumber { text, pos } => {
+                write!(f, "{pos}: invalid number '{text}'")
+            }
+            LexerError::InvalidFlag { ch, pos } => {
+                write!(f, "{pos}: invalid arc flag '{ch}'")
+            }
+        }
+    }
+}
+
+impl Error for LexerError {}
+
 pub(crate) struct Lexer<'a> {
-    input: Peekable<Chars<'a>>,
+    input: &'a str,
+    bytes: &'a [u8],
+    pos: Position,
+    // Whether the last token was a number, commas may only follow one
+    after_number: bool,
 }
 
 impl<'a> Lexer<'a> {
     pub(crate) fn new(input: &'a str) -> Self {
         Lexer {
-            input: input.chars().peekable(),
+            input,
+            bytes: input.as_bytes(),
+            pos: Position::START,
+            after_number: false,
+        }
+    }
+
+    /// Current position, just past the last consumed character.
+    pub(crate) fn position(&self) -> Position {
+        self.pos
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos.offset).copied()
+    }
+
+    /// Consume one ASCII byte.
+    fn bump(&mut self) {
+        if self.bytes[self.pos.offset] == b'\n' {
+            self.pos.line += 1;
+            self.pos.column = 1;
+        } else {
+            self.pos.column += 1;
         }
+        self.pos.offset += 1;
     }
 
-    fn skip_whitespace_and_commas(&mut self) {
-        while let Some(&c) = self.input.peek() {
-            if c.is_whitespace() || c == ',' {
-                self.input.next();
+    /// Consume a whole character, which may span several bytes.
+    fn bump_char(&mut self) -> Option<char> {
+        let c = self.input[self.pos.offset..].chars().next()?;
+        if c.is_ascii() {
+            self.bump();
+        } else {
+            self.pos.offset += c.len_utf8();
+            self.pos.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Skip `wsp*` or a `comma_wsp` between two numbers.
+    fn skip_separators(&mut self) -> Result<(), LexerError> {
+        let mut comma = None;
+        while let Some(b) = self.peek() {
+            if b.is_ascii_whitespace() {
+                self.bump();
+            } else if b == b',' {
+                let pos = self.pos;
+                self.bump();
+                if comma.is_some() || !self.after_number {
+                    return Err(LexerError::UnexpectedCharacter { ch: ',', pos });
+                }
+                comma = Some(pos);
             } else {
                 break;
             }
         }
-    }
 
-    fn read_number(&mut self) -> Result<Token, LexerError> {
-        let mut num_str = String::new();
-        let mut has_decimal = false;
-        let mut has_exponent = false;
-
-        // We use a loop and peek to decide exactly when to stop
-        while let Some(&c) = self.input.peek() {
-            match c {
-                // A sign is only part of THIS number if it's the first char
-                // OR if it's immediately after an 'e' (scientific notation)
-                '-' | '+' => {
-                    if num_str.is_empty() || num_str.ends_with('e') || num_str.ends_with('E') {
-                        num_str.push(self.input.next().unwrap());
-                    } else {
-                        // It's a sign for the NEXT number, stop here
-                        break;
-                    }
-                }
-                '0'..='9' => {
-                    num_str.push(self.input.next().unwrap());
-                }
-                '.' if !has_decimal && !has_exponent => {
-                    has_decimal = true;
-                    num_str.push(self.input.next().unwrap());
-                }
-                'e' | 'E' if !has_exponent => {
-                    has_exponent = true;
-                    num_str.push(self.input.next().unwrap());
-                }
-                _ => break, // Any other char (comma, space, letter) stops the number
+        match (comma, self.peek()) {
+            (Some(pos), next) if !next.is_some_and(starts_number) => {
+                Err(LexerError::UnexpectedCharacter { ch: ',', pos })
             }
+            _ => Ok(()),
         }
+    }
+
+    fn read_number(&mut self) -> Result<Token, LexerError> {
+        let start = self.pos;
+        // Numbers are ASCII without line breaks
+        let len = number_len(&self.bytes[start.offset..]);
+        self.pos.offset += len;
+        self.pos.column += len;
 
-        num_str
-            .parse::<f64>()
+        let text = &self.input[start.offset..self.pos.offset];
+        parse_number(text)
             .map(Token::Number)
-            .map_err(|_| LexerError::InvalidNumber(num_str))
+            .ok_or_else(|| LexerError::InvalidNumber {
+                text: text.to_string(),
+                pos: start,
+            })
+    }
+
+    /// Read an arc flag, which may be written without a separator
+    /// before the next number ("a1 1 0 0110 10").
+    pub(crate) fn next_flag(&mut self) -> Option<Result<(Position, Token), LexerError>> {
+        if let Err(e) = self.skip_separators() {
+            return Some(Err(e));
+        }
+        let pos = self.pos;
+        match self.peek()? {
+            b @ (b'0' | b'1') => {
+                self.bump();
+                self.after_number = true;
+                let n = if b == b'1' { 1.0 } else { 0.0 };
+                Some(Ok((pos, Token::Number(n))))
+            }
+            // Leave commands to the parser, which reports the missing flag
+            b if b.is_ascii_alphabetic() => self.next(),
+            _ => {
+                let ch = self.bump_char()?;
+                Some(Err(LexerError::InvalidFlag { ch, pos }))
+            }
+        }
     }
 
-    fn is_valid_command(c: char) -> bool {
+    fn is_valid_command(c: u8) -> bool {
         matches!(
             c.to_ascii_lowercase(),
-            'm' | 'l' | 'h' | 'v' | 'c' | 's' | 'q' | 't' | 'a' | 'z'
+            b'm' | b'l' | b'h' | b'v' | b'c' | b's' | b'q' | b't' | b'a' | b'z'
         )
     }
 }
 
 impl<'a> Iterator for Lexer<'a> {
-    type Item = Result<Token, LexerError>;
+    type Item = Result<(Position, Token), LexerError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespace_and_commas();
+        if let Err(e) = self.skip_separators() {
+            return Some(Err(e));
+        }
 
         // Peek to see what's next
-        let c = *self.input.peek()?;
+        let b = self.peek()?;
+        let pos = self.pos;
+        self.after_number = false;
 
         // It's an alphabetic character
-        if c.is_ascii_alphabetic() {
-            self.input.next(); // Consume it
-            if Self::is_valid_command(c) {
-                return Some(Ok(Token::Command(c)));
+        if b.is_ascii_alphabetic() {
+            self.bump(); // Consume it
+            let c = b as char;
+            if Self::is_valid_command(b) {
+                return Some(Ok((pos, Token::Command(c))));
             } else {
-                return Some(Err(LexerError::InvalidCommand(c)));
+                return Some(Err(LexerError::InvalidCommand { ch: c, pos }));
             }
         }
 
         // It's a number, a sign, or a decimal point
-        if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
-            return Some(self.read_number());
+        if starts_number(b) {
+            let token = self.read_number();
+            self.after_number = token.is_ok();
+            return Some(token.map(|token| (pos, token)));
         }
 
         // It's a character that shouldn't be here (e.g. #, $, %)
-        let unknown = self.input.next().unwrap();
-        Some(Err(LexerError::UnexpectedCharacter(unknown)))
+        let ch = self.bump_char()?;
+        Some(Err(LexerError::UnexpectedCharacter { ch, pos }))
+    }
+}
+
+/// Length of the number at the start of `bytes`, which may not be a valid one.
+pub(crate) fn number_len(bytes: &[u8]) -> usize {
+    let mut len = 0;
+    let mut has_decimal = false;
+    let mut has_exponent = false;
+
+    while let Some(&b) = bytes.get(len) {
+        match b {
+            // A sign is only part of THIS number if it's the first char
+            // OR if it's immediately after an 'e' (scientific notation)
+            b'-' | b'+' => {
+                if len == 0 || matches!(bytes[len - 1], b'e' | b'E') {
+                    len += 1;
+                } else {
+                    // It's a sign for the NEXT number, stop here
+                    break;
+                }
+            }
+            b'0'..=b'9' => len += 1,
+            b'.' if !has_decimal && !has_exponent => {
+                has_decimal = true;
+                len += 1;
+            }
+            b'e' | b'E' if !has_exponent => {
+                has_exponent = true;
+                len += 1;
+            }
+            _ => break, // Any other char (comma, space, letter) stops the number
+        }
+    }
+    len
+}
+
+pub(crate) fn starts_number(b: u8) -> bool {
+    b.is_ascii_digit() || b == b'-' || b == b'+' || b == b'.'
+}
+
+/// Powers of ten that are exact in an `f64`.
+const POW10: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// Parse a decimal number made of a sign, digits, a dot and an exponent.
+///
+/// Numbers with at most 15 significant digits and a small exponent, which
+/// is nearly all path data, are computed exactly with a single multiply or
+/// divide (Clinger's fast path). Everything else goes to `str::parse`.
+pub(crate) fn parse_number(text: &str) -> Option<f64> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    let negative = match bytes.first() {
+        Some(b'-') => {
+            i += 1;
+            true
+        }
+        Some(b'+') => {
+            i += 1;
+            false
+        }
+        _ => false,
+    };
+
+    let mut mantissa: u64 = 0;
+    let mut digits = 0;
+    let mut significant = 0;
+    let mut exponent: i64 = 0;
+
+    while let Some(b @ b'0'..=b'9') = bytes.get(i) {
+        if significant > 0 || *b != b'0' {
+            significant += 1;
+            mantissa = mantissa.wrapping_mul(10).wrapping_add((b - b'0') as u64);
+        }
+        digits += 1;
+        i += 1;
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        while let Some(b @ b'0'..=b'9') = bytes.get(i) {
+            if significant > 0 || *b != b'0' {
+                significant += 1;
+                mantissa = mantissa.wrapping_mul(10).wrapping_add((b - b'0') as u64);
+            }
+            exponent -= 1;
+            digits += 1;
+            i += 1;
+        }
     }
+    if digits == 0 {
+        return None;
+    }
+
+    if let Some(b'e' | b'E') = bytes.get(i) {
+        i += 1;
+        let exp_negative = match bytes.get(i) {
+            Some(b'-') => {
+                i += 1;
+                true
+            }
+            Some(b'+') => {
+                i += 1;
+                false
+            }
+            _ => false,
+        };
+        let exp_start = i;
+        let mut exp: i64 = 0;
+        while let Some(b @ b'0'..=b'9') = bytes.get(i) {
+            exp = (exp * 10 + (b - b'0') as i64).min(1 << 20);
+            i += 1;
+        }
+        if i == exp_start {
+            return None;
+        }
+        exponent += if exp_negative { -exp } else { exp };
+    }
+    if i != bytes.len() {
+        return None;
+    }
+
+    if significant <= 15 && (-22..=22).contains(&exponent) {
+        let m = mantissa as f64;
+        let value = if exponent < 0 {
+            m / POW10[-exponent as usize]
+        } else {
+            m * POW10[exponent as usize]
+        };
+        return Some(if negative { -value } else { value });
+    }
+
+    text.parse().ok()
 }
 
 #[cfg(test)]
@@ -145,12 +423,75 @@ mod t {
 
         for (s, expected) in test_data {
             let lx = Lexer::new(s);
-            let ls: Result<Vec<_>, _> = lx.collect();
+            let ls: Result<Vec<_>, _> = lx.map(|r| r.map(|(_, tok)| tok)).collect();
             assert!(ls.is_ok());
             assert_eq!(ls.unwrap(), expected);
         }
     }
 
+    #[test]
+    fn positions() {
+        let lx = Lexer::new("M 10,5\n  L -3 4");
+        let pos: Vec<_> = lx
+            .map(|r| {
+                let p = r.unwrap().0;
+                (p.offset, p.line, p.column)
+            })
+            .collect();
+        assert_eq!(
+            pos,
+            [
+                (0, 1, 1),
+                (2, 1, 3),
+                (5, 1, 6),
+                (9, 2, 3),
+                (11, 2, 5),
+                (14, 2, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        let test_data = [
+            "0",
+            "-0",
+            "+1",
+            "1.",
+            ".5",
+            "-.5e-3",
+            "1E+5",
+            "0.1",
+            "0.3",
+            "123.456",
+            "1e22",
+            "1e23",
+            "1e-22",
+            "1e-23",
+            "9007199254740993",
+            "1.7976931348623157e308",
+            "1e309",
+            "4.9e-324",
+            "1e-400",
+            "000001.0000010",
+            "3.14159265358979323846",
+            "0.000000000000001",
+            "123456789012345.6",
+            "1e99999999999",
+        ];
+        for s in test_data {
+            let n = parse_number(s).unwrap();
+            let expected = s.parse::<f64>().unwrap();
+            assert_eq!(n.to_bits(), expected.to_bits(), "{s}");
+        }
+
+        for s in [
+            "", "-", ".", "+.", "e5", "1e", "1e+", "1.2.3", "1e5e5", "--1",
+        ] {
+            assert_eq!(parse_number(s), None, "{s}");
+        }
+    }
+
     #[test]
     fn invalid() {
         let invalids = ["M 8 7 X 7 8"];
@@ -159,5 +500,11 @@ mod t {
             let ls: Result<Vec<_>, _> = lx.collect();
             assert!(ls.is_err());
         }
+
+        let err = Lexer::new("M 1\n 2 #").find_map(|r| r.err()).unwrap();
+        assert_eq!(err.to_string(), "line 2, col 4: unexpected character '#'");
+
+        let err = Lexer::new("M 1 é").find_map(|r| r.err()).unwrap();
+        assert_eq!(err.to_string(), "line 1, col 5: unexpected character 'é'");
     }
 }
@@ -25,7 +25,7 @@
 //! let sp = sp.fit(&rect, true, true);
 //!
 //! // Rotate 35 degrees by its center point and scale up
-//! let center = sp.bbox().center();
+//! let center = sp.bbox().ok_or("empty path")?.center();
 //! let m = svgpath::Matrix::new()
 //!     .rotate_by(35.0, center.x, center.y)
 //!     .scale(2.0, 2.0);
@@ -49,17 +49,39 @@
 //! ```
 //!
 
+mod area;
 mod bbox;
+mod boolean;
+mod flatten;
+mod hit;
+mod intersect;
 mod lexer;
 mod matrix;
 mod parser;
 mod path;
+mod raw;
 mod reverse;
+mod roots;
+mod segment;
 mod simplify;
+mod stroke;
+mod transform;
 mod utils;
+mod writer;
 
 pub use bbox::BBox;
-pub use matrix::Matrix;
-pub use parser::{Command, ParserError, Point};
-pub use path::{CommandF32, Path, SimplePath, parse};
+pub use boolean::BooleanOp;
+pub use flatten::{Polyline, Polylines};
+pub use hit::{FillRule, NearestPoint};
+pub use intersect::Intersection;
+pub use lexer::{LexerError, Position, Token};
+pub use matrix::{Decomposition, Matrix, Transformed};
+pub use parser::{Command, ParserError, PathParser, Point};
+pub use path::{CommandF32, Path, SimplePath, parse, parse_lenient};
+pub use raw::{RawNumber, RawPath, RawSegment, parse_raw};
+pub use segment::Segment;
+pub use simplify::Simplified;
+pub use stroke::{LineCap, LineJoin, StrokeStyle};
+pub use transform::{Transform, TransformError, TransformList};
 pub use utils::Rect;
+pub use writer::{Coordinates, Separator, WriteOptions};
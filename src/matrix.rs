@@ -1,7 +1,9 @@
 use std::fmt;
+use std::iter::FusedIterator;
 
-use crate::Command;
-use crate::parser::format_n;
+use crate::transform::{TransformError, TransformList};
+use crate::writer::{WriteOptions, Writer};
+use crate::{Command, ParserError, PathParser, Point};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Matrix {
@@ -13,6 +15,28 @@ pub struct Matrix {
     pub f: f64,
 }
 
+/// Components of a [`Matrix`], angles are in degrees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decomposition {
+    pub translate_x: f64,
+    pub translate_y: f64,
+    pub rotate: f64,
+    pub skew_x: f64,
+    pub scale_x: f64,
+    pub scale_y: f64,
+}
+
+impl Decomposition {
+    /// Build the matrix back from its components.
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix::new()
+            .translate(self.translate_x, self.translate_y)
+            .rotate(self.rotate)
+            .skew_x(self.skew_x)
+            .scale(self.scale_x, self.scale_y)
+    }
+}
+
 impl Default for Matrix {
     fn default() -> Self {
         Self::new()
@@ -33,13 +57,71 @@ impl Matrix {
     }
 
     /// Transforms a point: x' = ax + cy + e, y' = bx + dy + f
-    fn transform_point(&self, x: f64, y: f64) -> [f64; 2] {
+    pub fn transform_point(&self, x: f64, y: f64) -> [f64; 2] {
         [
             self.a * x + self.c * y + self.e,
             self.b * x + self.d * y + self.f,
         ]
     }
 
+    /// Transforms a vector, ignoring the translation: x' = ax + cy, y' = bx + dy
+    pub fn transform_vector(&self, x: f64, y: f64) -> [f64; 2] {
+        [self.a * x + self.c * y, self.b * x + self.d * y]
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::new()
+    }
+
+    pub fn is_invertible(&self) -> bool {
+        let det = self.determinant();
+        det != 0.0 && det.is_finite()
+    }
+
+    /// The matrix undoing this one, `None` if it collapses the plane onto a
+    /// line or a point.
+    pub fn inverse(&self) -> Option<Self> {
+        if !self.is_invertible() {
+            return None;
+        }
+        let det = self.determinant();
+        Some(Self {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    /// Split into components such that the matrix equals
+    /// `translate(tx, ty) rotate(rotate) skewX(skew_x) scale(sx, sy)`.
+    ///
+    /// A reflection is expressed by a negative `scale_y`. Returns `None` for
+    /// matrices that are not invertible.
+    pub fn decompose(&self) -> Option<Decomposition> {
+        if !self.is_invertible() {
+            return None;
+        }
+        let det = self.determinant();
+        let scale_x = self.a.hypot(self.b);
+        let scale_y = det / scale_x;
+        let skew = (self.a * self.c + self.b * self.d) / det;
+        Some(Decomposition {
+            translate_x: self.e,
+            translate_y: self.f,
+            rotate: self.b.atan2(self.a).to_degrees(),
+            skew_x: skew.atan().to_degrees(),
+            scale_x,
+            scale_y,
+        })
+    }
+
     /// Multiply two matrices (Combine transformations)
     #[must_use]
     pub fn multiply(&self, other: &Matrix) -> Self {
@@ -53,6 +135,129 @@ impl Matrix {
         }
     }
 
+    /// Apply the matrix to an absolute command.
+    ///
+    /// `H` and `V` can not be transformed without the current point and
+    /// give `None`, [`PathParser::transform`] keeps track of it.
+    pub fn transform_command(&self, cmd: &Command) -> Option<Command> {
+        let p = |x, y| self.transform_point(x, y);
+        Some(match *cmd {
+            Command::Move { x, y } => {
+                let [x, y] = p(x, y);
+                Command::Move { x, y }
+            }
+            Command::Line { x, y } => {
+                let [x, y] = p(x, y);
+                Command::Line { x, y }
+            }
+            Command::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let [x1, y1] = p(x1, y1);
+                let [x2, y2] = p(x2, y2);
+                let [x, y] = p(x, y);
+                Command::Cubic {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                }
+            }
+            // Reflected control points stay reflected under affine maps
+            Command::SmoothCubic { x2, y2, x, y } => {
+                let [x2, y2] = p(x2, y2);
+                let [x, y] = p(x, y);
+                Command::SmoothCubic { x2, y2, x, y }
+            }
+            Command::Quadratic { x1, y1, x, y } => {
+                let [x1, y1] = p(x1, y1);
+                let [x, y] = p(x, y);
+                Command::Quadratic { x1, y1, x, y }
+            }
+            Command::SmoothQuadratic { x, y } => {
+                let [x, y] = p(x, y);
+                Command::SmoothQuadratic { x, y }
+            }
+            Command::Arc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc_flag,
+                sweep_flag,
+                x,
+                y,
+            } => {
+                let (rx, ry, x_axis_rotation) = self.transform_ellipse(rx, ry, x_axis_rotation);
+                let [x, y] = p(x, y);
+                Command::Arc {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc_flag,
+                    // A reflection reverses the direction of travel
+                    sweep_flag: sweep_flag != (self.a * self.d - self.b * self.c < 0.0),
+                    x,
+                    y,
+                }
+            }
+            Command::Close => Command::Close,
+            Command::Horizontal { .. } | Command::Vertical { .. } => return None,
+        })
+    }
+
+    /// Radii and rotation (in degrees) of the ellipse `(rx, ry, rotation)`
+    /// after applying the matrix.
+    fn transform_ellipse(&self, rx: f64, ry: f64, rotation: f64) -> (f64, f64, f64) {
+        let (rx, ry) = (rx.abs(), ry.abs());
+
+        // Translation, uniform scale and flips keep the axes
+        if self.b == 0.0 && self.c == 0.0 && self.a.abs() == self.d.abs() {
+            let rotation = if self.a == self.d {
+                rotation
+            } else {
+                -rotation
+            };
+            return (rx * self.a.abs(), ry * self.a.abs(), rotation);
+        }
+
+        // The ellipse is the unit circle mapped by the matrix times
+        // rotate(rotation) scale(rx, ry), its radii and rotation are the
+        // singular values and the left rotation of the product.
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let p = (self.a * cos + self.c * sin) * rx;
+        let q = (self.c * cos - self.a * sin) * ry;
+        let r = (self.b * cos + self.d * sin) * rx;
+        let s = (self.d * cos - self.b * sin) * ry;
+
+        let e = (p + s) / 2.0;
+        let f = (p - s) / 2.0;
+        let g = (r + q) / 2.0;
+        let h = (r - q) / 2.0;
+        let big = e.hypot(h);
+        let small = f.hypot(g);
+        let phi = (h.atan2(e) + g.atan2(f)) / 2.0;
+        let (r1, r2) = (big + small, (big - small).abs());
+
+        // Of the two equal descriptions keep the one closest to the input
+        let mut turn = (phi.to_degrees() - rotation).rem_euclid(180.0);
+        if turn > 90.0 {
+            turn -= 180.0;
+        }
+        if turn.abs() <= 45.0 {
+            (r1, r2, rotation + turn)
+        } else {
+            let turn = if turn > 0.0 { turn - 90.0 } else { turn + 90.0 };
+            (r2, r1, rotation + turn)
+        }
+    }
+
     #[must_use]
     pub fn translate(&self, tx: f64, ty: f64) -> Self {
         let m = Self {
@@ -139,143 +344,126 @@ impl Matrix {
         self.multiply(&m)
     }
 
-    /// Parses an SVG transform string
-    pub fn parse(input: &str) -> Result<Self, String> {
-        let mut result = Matrix::new();
+    /// Format as `matrix(a b c d e f)` using `opts`.
+    pub fn to_string_with(&self, opts: &WriteOptions) -> String {
+        let mut w = Writer::new(opts);
+        w.matrix(&[self.a, self.b, self.c, self.d, self.e, self.f]);
+        w.finish()
+    }
 
-        // Replace commas/parens with spaces to easily tokenize,
-        // but keep the function names identifiable.
-        let normalized = input
-            .replace(',', " ")
-            .replace('(', " ( ")
-            .replace(')', " ) ");
+    /// Parse the value of an SVG `transform` attribute, such as
+    /// `"translate(10 20) rotate(45)"`.
+    ///
+    /// Use [`TransformList::parse`] to keep the individual transforms.
+    pub fn parse(input: &str) -> Result<Self, TransformError> {
+        Ok(TransformList::parse(input)?.to_matrix())
+    }
 
-        let mut tokens = normalized.split_whitespace().peekable();
+    /// Parse the value of the CSS `transform` property, such as
+    /// `"translateX(10px) rotate(0.25turn)"`.
+    ///
+    /// Lengths must be in `px` and 3D functions are not supported.
+    pub fn parse_css(input: &str) -> Result<Self, TransformError> {
+        Ok(TransformList::parse_css(input)?.to_matrix())
+    }
+}
 
-        while let Some(func_name) = tokens.next() {
-            // Expect an opening parenthesis next
-            match tokens.next() {
-                Some("(") => {}
-                _ => return Err(format!("Expected '(' after {}", func_name)),
-            }
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with(&WriteOptions::default()))
+    }
+}
 
-            // Collect all numbers until the closing parenthesis
-            let mut nums = Vec::new();
-            while let Some(&next_token) = tokens.peek() {
-                if next_token == ")" {
-                    // Consume ')'
-                    tokens.next();
-                    break;
-                }
-                let n: f64 = tokens
-                    .next()
-                    .unwrap()
-                    .parse()
-                    .map_err(|_| format!("Invalid number in {}", func_name))?;
-                nums.push(n);
-            }
+/// Move `current` and `start` to where they are after the absolute `cmd`.
+fn advance(cmd: &Command, current: &mut Point, start: &mut Point) {
+    *current = match *cmd {
+        Command::Move { x, y } => {
+            *start = Point { x, y };
+            *start
+        }
+        Command::Line { x, y }
+        | Command::Cubic { x, y, .. }
+        | Command::SmoothCubic { x, y, .. }
+        | Command::Quadratic { x, y, .. }
+        | Command::SmoothQuadratic { x, y }
+        | Command::Arc { x, y, .. } => Point { x, y },
+        Command::Horizontal { x } => Point { x, y: current.y },
+        Command::Vertical { y } => Point { x: current.x, y },
+        Command::Close => *start,
+    };
+}
 
-            if nums.is_empty() {
-                return Err(format!("No arguments provided for {}", func_name));
-            }
+/// Transform absolute commands, keeping `H` and `V` when the matrix maps
+/// them to horizontal or vertical lines and using `L` otherwise.
+pub(crate) fn transform_path(commands: &[Command], m: &Matrix) -> Vec<Command> {
+    let mut current = Point { x: 0.0, y: 0.0 };
+    let mut start = current;
+    let axis_aligned = m.b == 0.0 && m.c == 0.0;
+    let axis_swapped = m.a == 0.0 && m.d == 0.0;
 
-            match func_name.to_lowercase().as_str() {
-                "matrix" if nums.len() == 6 => {
-                    let m = Matrix {
-                        a: nums[0],
-                        b: nums[1],
-                        c: nums[2],
-                        d: nums[3],
-                        e: nums[4],
-                        f: nums[5],
-                    };
-                    result = result.multiply(&m);
-                }
-                "translate" => {
-                    let tx = nums[0];
-                    let ty = nums.get(1).cloned().unwrap_or(0.0);
-                    result = result.translate(tx, ty);
-                }
-                "scale" => {
-                    let sx = nums[0];
-                    let sy = nums.get(1).cloned().unwrap_or(sx);
-                    result = result.scale(sx, sy);
-                }
-                "rotate" => {
-                    let angle = nums[0];
-                    if nums.len() == 3 {
-                        result = result.rotate_by(angle, nums[1], nums[2]);
-                    } else {
-                        result = result.rotate(angle);
-                    }
-                }
-                "skewx" => {
-                    result = result.skew_x(nums[0]);
-                }
-                "skewy" => {
-                    result = result.skew_y(nums[0]);
-                }
-                _ => return Err(format!("Unknown or invalid transform: {}", func_name)),
-            };
-        }
+    let mut result = Vec::with_capacity(commands.len());
+    for cmd in commands {
+        advance(cmd, &mut current, &mut start);
 
-        Ok(result)
+        let cmd = match *cmd {
+            Command::Horizontal { x } if axis_aligned => Command::Horizontal { x: m.a * x + m.e },
+            Command::Horizontal { x } if axis_swapped => Command::Vertical { y: m.b * x + m.f },
+            Command::Vertical { y } if axis_aligned => Command::Vertical { y: m.d * y + m.f },
+            Command::Vertical { y } if axis_swapped => Command::Horizontal { x: m.c * y + m.e },
+            Command::Horizontal { .. } | Command::Vertical { .. } => {
+                let [x, y] = m.transform_point(current.x, current.y);
+                Command::Line { x, y }
+            }
+            _ => m.transform_command(cmd).unwrap(),
+        };
+        result.push(cmd);
     }
+    result
 }
 
-impl fmt::Display for Matrix {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "matrix({} {} {} {} {} {})",
-            format_n(self.a),
-            format_n(self.b),
-            format_n(self.c),
-            format_n(self.d),
-            format_n(self.e),
-            format_n(self.f)
-        )
+/// Iterator over the transformed commands of a [`PathParser`], created by
+/// [`PathParser::transform`].
+///
+/// `H` and `V` become `L`, as they may not stay axis-aligned.
+pub struct Transformed<'a> {
+    parser: PathParser<'a>,
+    matrix: Matrix,
+    current: Point,
+    start: Point,
+}
+
+impl<'a> Transformed<'a> {
+    pub(crate) fn new(parser: PathParser<'a>, matrix: &Matrix) -> Self {
+        Self {
+            parser,
+            matrix: matrix.clone(),
+            current: Point { x: 0.0, y: 0.0 },
+            start: Point { x: 0.0, y: 0.0 },
+        }
     }
 }
 
-pub(crate) fn transform_path(commands: &[Command], matrix: &Matrix) -> Vec<Command> {
-    commands
-        .iter()
-        .filter_map(|cmd| match *cmd {
-            Command::Move { x, y } => {
-                let [x, y] = matrix.transform_point(x, y);
-                Some(Command::Move { x, y })
-            }
-            Command::Line { x, y } => {
-                let [x, y] = matrix.transform_point(x, y);
-                Some(Command::Line { x, y })
-            }
-            Command::Cubic {
-                x1,
-                y1,
-                x2,
-                y2,
-                x,
-                y,
-            } => {
-                let [x1, y1] = matrix.transform_point(x1, y1);
-                let [x2, y2] = matrix.transform_point(x2, y2);
-                let [x, y] = matrix.transform_point(x, y);
-                Some(Command::Cubic {
-                    x1,
-                    y1,
-                    x2,
-                    y2,
-                    x,
-                    y,
-                })
+impl Iterator for Transformed<'_> {
+    type Item = Result<Command, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cmd = match self.parser.next()? {
+            Ok(cmd) => cmd,
+            Err(e) => return Some(Err(e)),
+        };
+        advance(&cmd, &mut self.current, &mut self.start);
+        Some(Ok(match self.matrix.transform_command(&cmd) {
+            Some(cmd) => cmd,
+            None => {
+                let [x, y] = self.matrix.transform_point(self.current.x, self.current.y);
+                Command::Line { x, y }
             }
-            Command::Close => Some(Command::Close),
-            _ => None,
-        })
-        .collect()
+        }))
+    }
 }
 
+impl FusedIterator for Transformed<'_> {}
+
 #[cfg(test)]
 mod t {
     use super::*;
@@ -298,6 +486,38 @@ mod t {
         assert_eq!(m.to_string(), "matrix(0.98 -0.17 0.5 0.42 -44.16 61.26)");
     }
 
+    #[test]
+    fn inverse() {
+        let m = Matrix::new()
+            .translate(15.0, -7.0)
+            .rotate(34.0)
+            .skew_x(20.0)
+            .scale(2.0, -0.5);
+        let inv = m.inverse().unwrap();
+        let [x, y] = m.transform_point(3.0, 4.0);
+        let [x, y] = inv.transform_point(x, y);
+        assert!((x - 3.0).abs() < 1e-12 && (y - 4.0).abs() < 1e-12);
+
+        let id = m.multiply(&inv);
+        for (v, w) in [id.a, id.b, id.c, id.d, id.e, id.f]
+            .iter()
+            .zip([1.0, 0.0, 0.0, 1.0, 0.0, 0.0])
+        {
+            assert!((v - w).abs() < 1e-12);
+        }
+        assert!(Matrix::new().inverse().unwrap().is_identity());
+        assert_eq!(Matrix::new().scale(0.0, 1.0).inverse(), None);
+        assert_eq!(m.transform_vector(0.0, 0.0), [0.0, 0.0]);
+
+        let d = m.decompose().unwrap();
+        assert!((d.rotate - 34.0).abs() < 1e-12);
+        assert!((d.skew_x - 20.0).abs() < 1e-12);
+        assert!((d.scale_x - 2.0).abs() < 1e-12);
+        assert!((d.scale_y + 0.5).abs() < 1e-12);
+        assert_eq!((d.translate_x, d.translate_y), (15.0, -7.0));
+        assert_eq!(d.to_matrix().to_string(), m.to_string());
+    }
+
     #[test]
     fn parse_str() {
         let m1 = Matrix::new()
@@ -321,5 +541,13 @@ mod t {
         let m2 = m2.unwrap();
         assert_eq!(m1, m2);
         assert_eq!(m1.to_string(), m2.to_string());
+
+        let s = "translate(5px, 13px) scale(1.75) rotate(35deg) translateX(-8px)";
+        let m3 = Matrix::parse_css(s).unwrap();
+        let m4 = Matrix::parse("translate(5 13) scale(1.75) rotate(35) translate(-8)").unwrap();
+        assert_eq!(m3, m4);
+
+        assert!(Matrix::parse("").unwrap().is_identity());
+        assert!(Matrix::parse_css("none").unwrap().is_identity());
     }
 }
@@ -1,8 +1,11 @@
+use crate::lexer::{Lexer, LexerError, Position, Token};
+use crate::matrix::{Matrix, Transformed};
+use crate::segment::Segment;
+use crate::simplify::Simplified;
+use crate::writer::{Coordinates, WriteOptions, Writer};
 use std::error::Error;
 use std::fmt;
-use std::iter::Peekable;
-
-use crate::lexer::{Lexer, LexerError, Token};
+use std::iter::FusedIterator;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Command {
@@ -56,121 +59,132 @@ pub enum Command {
     Close,
 }
 
-impl fmt::Display for Command {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Command::Move { x, y } => {
-                write!(f, "M {} {}", format_n(*x), format_n(*y))
-            }
-            Command::Line { x, y } => {
-                write!(f, "L {} {}", format_n(*x), format_n(*y))
-            }
-            Command::Horizontal { x } => {
-                write!(f, "H {}", format_n(*x))
-            }
-            Command::Vertical { y } => {
-                write!(f, "V {}", format_n(*y))
-            }
-            Command::Cubic {
-                x1,
-                y1,
-                x2,
-                y2,
-                x,
-                y,
-            } => {
-                write!(
-                    f,
-                    "C {} {} {} {} {} {}",
-                    format_n(*x1),
-                    format_n(*y1),
-                    format_n(*x2),
-                    format_n(*y2),
-                    format_n(*x),
-                    format_n(*y)
-                )
-            }
-            Command::Quadratic { x1, y1, x, y } => {
-                write!(
-                    f,
-                    "Q {} {} {} {}",
-                    format_n(*x1),
-                    format_n(*y1),
-                    format_n(*x),
-                    format_n(*y)
-                )
-            }
-            Command::SmoothCubic { x2, y2, x, y } => {
-                write!(
-                    f,
-                    "S {} {} {} {}",
-                    format_n(*x2),
-                    format_n(*y2),
-                    format_n(*x),
-                    format_n(*y)
-                )
-            }
-            Command::SmoothQuadratic { x, y } => {
-                write!(f, "T {} {}", format_n(*x), format_n(*y))
-            }
-            Command::Arc {
-                rx,
-                ry,
-                x_axis_rotation,
-                large_arc_flag,
-                sweep_flag,
-                x,
-                y,
-            } => {
-                write!(
-                    f,
-                    "A {} {} {} {} {} {} {}",
-                    format_n(*rx),
-                    format_n(*ry),
-                    format_n(*x_axis_rotation),
-                    if *large_arc_flag { 1 } else { 0 },
-                    if *sweep_flag { 1 } else { 0 },
-                    format_n(*x),
-                    format_n(*y)
-                )
-            }
-            Command::Close => write!(f, "Z"),
-        }
+impl Command {
+    /// Format this command using `opts`.
+    ///
+    /// A single command has no current point, so it is always written with
+    /// absolute coordinates.
+    pub fn to_string_with(&self, opts: &WriteOptions) -> String {
+        let opts = WriteOptions {
+            coordinates: Coordinates::Absolute,
+            ..opts.clone()
+        };
+        let mut w = Writer::new(&opts);
+        w.command(self);
+        w.finish()
+    }
+
+    /// Split a `Cubic` starting at `from` at parameter `t` in `[0, 1]`.
+    ///
+    /// Returns `None` for any other command.
+    pub fn split_cubic(&self, from: Point, t: f64) -> Option<(Command, Command)> {
+        let Command::Cubic {
+            x1,
+            y1,
+            x2,
+            y2,
+            x,
+            y,
+        } = *self
+        else {
+            return None;
+        };
+        let seg = Segment::Cubic {
+            from,
+            ctrl1: Point { x: x1, y: y1 },
+            ctrl2: Point { x: x2, y: y2 },
+            to: Point { x, y },
+        };
+        let (a, b) = seg.split(t);
+        Some((a.to_command(), b.to_command()))
     }
 }
 
-pub(crate) fn format_n(n: f64) -> String {
-    if n.fract() == 0.0 {
-        format!("{:.0}", n)
-    } else {
-        format!("{:.2}", n)
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .to_string()
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with(&WriteOptions::default()))
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum ParserError {
     LexerErr(LexerError),
-    UnexpectedToken(Token),
+    UnexpectedToken {
+        token: Token,
+        pos: Position,
+    },
     MissingArgument {
         cmd: char,
         expected: usize,
         found: usize,
+        pos: Position,
+    },
+    NoStartingCommand {
+        pos: Position,
+    },
+    EndOfStream {
+        pos: Position,
     },
-    NoStartingCommand,
-    EndOfStream,
+}
+
+impl ParserError {
+    /// Where in the input this error occurred.
+    pub fn position(&self) -> Position {
+        match self {
+            ParserError::LexerErr(e) => e.position(),
+            ParserError::UnexpectedToken { pos, .. }
+            | ParserError::MissingArgument { pos, .. }
+            | ParserError::NoStartingCommand { pos }
+            | ParserError::EndOfStream { pos } => *pos,
+        }
+    }
+
+    /// The offending line of `input` followed by a caret under the error position.
+    ///
+    /// `input` must be the string that produced this error.
+    pub fn snippet(&self, input: &str) -> String {
+        let pos = self.position();
+        let line = input.lines().nth(pos.line - 1).unwrap_or("");
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        format!("{line}\n{:>width$}", "^", width = pos.column)
+    }
 }
 
 impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // TODO
-        write!(f, "parser error")
+        match self {
+            ParserError::LexerErr(e) => write!(f, "{e}"),
+            ParserError::UnexpectedToken { token, pos } => {
+                write!(f, "{pos}: unexpected {token}")
+            }
+            ParserError::MissingArgument {
+                cmd,
+                expected,
+                found,
+                pos,
+            } => {
+                let s = if *expected == 1 { "" } else { "s" };
+                write!(
+                    f,
+                    "{pos}: expected {expected} number{s} after '{cmd}', found {found}"
+                )
+            }
+            ParserError::NoStartingCommand { pos } => {
+                write!(f, "{pos}: path data must start with a moveto command")
+            }
+            ParserError::EndOfStream { pos } => write!(f, "{pos}: empty path data"),
+        }
     }
 }
 
-impl Error for ParserError {}
+impl Error for ParserError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParserError::LexerErr(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<LexerError> for ParserError {
     fn from(err: LexerError) -> Self {
@@ -178,229 +192,370 @@ impl From<LexerError> for ParserError {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
 }
 
-pub(crate) struct Parser<'a> {
-    lexer: Peekable<Lexer<'a>>,
-    cursor: Point,
+/// A lexed token with its start position and end byte offset.
+type Spanned = Result<(Position, Token, usize), LexerError>;
+
+/// Number of arguments taken by each command letter.
+pub(crate) fn arg_count(cmd: char) -> usize {
+    match cmd.to_ascii_uppercase() {
+        'M' | 'L' | 'T' => 2,
+        'H' | 'V' => 1,
+        'C' => 6,
+        'S' | 'Q' => 4,
+        'A' => 7,
+        _ => 0,
+    }
+}
+
+/// A single command as written in the source, before resolving relative coordinates.
+pub(crate) struct RawCommand {
+    /// Command letter in effect; `L` or `l` for the implicit lineto after a moveto
+    pub(crate) letter: char,
+    /// The letter was omitted in the source (implicit repeat)
+    pub(crate) implicit: bool,
+    /// Byte offset of the letter, or of the first number for implicit commands
+    pub(crate) start: usize,
+    args: [f64; 7],
+    spans: [(usize, usize); 7],
+}
+
+impl RawCommand {
+    pub(crate) fn args(&self) -> &[f64] {
+        &self.args[..arg_count(self.letter)]
+    }
+
+    /// Byte ranges of the arguments in the source.
+    pub(crate) fn spans(&self) -> &[(usize, usize)] {
+        &self.spans[..arg_count(self.letter)]
+    }
+}
+
+/// Tracks the current point to convert commands into absolute coordinates.
+pub(crate) struct Cursor {
+    current: Point,
     start_point: Point,
-    last_control_point: Option<Point>,
 }
 
-impl<'a> Parser<'a> {
-    pub(crate) fn new(input: &'a str) -> Self {
+impl Cursor {
+    pub(crate) fn new() -> Self {
         Self {
-            lexer: Lexer::new(input).peekable(),
-            cursor: Point { x: 0.0, y: 0.0 },
+            current: Point { x: 0.0, y: 0.0 },
             start_point: Point { x: 0.0, y: 0.0 },
-            last_control_point: None,
         }
     }
 
-    pub(crate) fn parse(&mut self) -> Result<Vec<Command>, ParserError> {
-        if self.lexer.peek().is_none() {
-            return Err(ParserError::EndOfStream);
-        }
+    /// Converts command `c` with arguments `a` into an absolute command
+    /// and moves the cursor to its end point.
+    pub(crate) fn absolute(&mut self, c: char, a: &[f64]) -> Command {
+        let (dx, dy) = if c.is_lowercase() {
+            (self.current.x, self.current.y)
+        } else {
+            (0.0, 0.0)
+        };
 
-        let mut commands = Vec::new();
-        let mut last_cmd_char: Option<char> = None;
-
-        while let Some(token_result) = self.lexer.peek() {
-            // Check for leading numbers ("6" or "6 M 0 0")
-            if last_cmd_char.is_none()
-                && let Ok(Token::Number(_)) = token_result
-            {
-                return Err(ParserError::NoStartingCommand);
+        let cmd = match c.to_ascii_uppercase() {
+            'M' => {
+                let (x, y) = (a[0] + dx, a[1] + dy);
+                self.start_point = Point { x, y };
+                Command::Move { x, y }
             }
+            'L' => Command::Line {
+                x: a[0] + dx,
+                y: a[1] + dy,
+            },
+            'H' => Command::Horizontal { x: a[0] + dx },
+            'V' => Command::Vertical { y: a[0] + dy },
+            'C' => Command::Cubic {
+                x1: a[0] + dx,
+                y1: a[1] + dy,
+                x2: a[2] + dx,
+                y2: a[3] + dy,
+                x: a[4] + dx,
+                y: a[5] + dy,
+            },
+            'S' => Command::SmoothCubic {
+                x2: a[0] + dx,
+                y2: a[1] + dy,
+                x: a[2] + dx,
+                y: a[3] + dy,
+            },
+            'Q' => Command::Quadratic {
+                x1: a[0] + dx,
+                y1: a[1] + dy,
+                x: a[2] + dx,
+                y: a[3] + dy,
+            },
+            'T' => Command::SmoothQuadratic {
+                x: a[0] + dx,
+                y: a[1] + dy,
+            },
+            'A' => Command::Arc {
+                rx: a[0],
+                ry: a[1],
+                x_axis_rotation: a[2],
+                large_arc_flag: a[3] != 0.0,
+                sweep_flag: a[4] != 0.0,
+                x: a[5] + dx,
+                y: a[6] + dy,
+            },
+            _ => Command::Close,
+        };
 
-            // Consume the token we just peeked
-            let token = self.lexer.next().unwrap()?;
-
-            match token {
-                Token::Command(c) => {
-                    // Handle "MM" by validating current command logic
-                    let mut current_cmd_char = c;
-                    commands.push(self.process_command(current_cmd_char)?);
-
-                    // Handle Implicit Commands and repeated letters
-                    while let Some(token_result) = self.lexer.peek() {
-                        match token_result {
-                            Ok(Token::Number(_)) => {
-                                if current_cmd_char.eq_ignore_ascii_case(&'M') {
-                                    current_cmd_char = if current_cmd_char.is_lowercase() {
-                                        'l'
-                                    } else {
-                                        'L'
-                                    };
-                                }
-                                commands.push(self.process_command(current_cmd_char)?);
-                            }
-                            // If another command follows immediately (e.g., "MM"),
-                            // the outer loop will handle it. We break here.
-                            _ => break,
-                        }
-                    }
-                    last_cmd_char = Some(current_cmd_char);
-                }
-                Token::Number(n) => return Err(ParserError::UnexpectedToken(Token::Number(n))),
-            }
+        self.current = match cmd {
+            Command::Horizontal { x } => Point {
+                x,
+                y: self.current.y,
+            },
+            Command::Vertical { y } => Point {
+                x: self.current.x,
+                y,
+            },
+            Command::Close => self.start_point,
+            Command::Move { x, y }
+            | Command::Line { x, y }
+            | Command::Cubic { x, y, .. }
+            | Command::SmoothCubic { x, y, .. }
+            | Command::Quadratic { x, y, .. }
+            | Command::SmoothQuadratic { x, y }
+            | Command::Arc { x, y, .. } => Point { x, y },
+        };
+        cmd
+    }
+}
+
+/// Streaming parser yielding absolute commands one at a time.
+///
+/// Numbers are read straight from the input without allocating. Iteration
+/// stops after the first error, and like [`parse`](crate::parse), empty path
+/// data is an error.
+pub struct PathParser<'a> {
+    parser: Parser<'a>,
+    cursor: Cursor,
+    started: bool,
+    done: bool,
+}
+
+impl<'a> PathParser<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Self {
+            parser: Parser::new(s),
+            cursor: Cursor::new(),
+            started: false,
+            done: false,
         }
-        Ok(commands)
     }
 
-    /// Internal logic to consume required numbers for a specific command char
-    /// and convert them to absolute coordinates.
-    fn process_command(&mut self, c: char) -> Result<Command, ParserError> {
-        let is_rel = c.is_lowercase();
-        let cmd_type = c.to_ascii_uppercase();
+    /// Convert the commands into simple ones on the fly, like
+    /// [`Path::simplify`](crate::Path::simplify).
+    pub fn simplify(self) -> Simplified<'a> {
+        Simplified::new(self)
+    }
 
-        match cmd_type {
-            'M' => {
-                let p = self.get_abs_point(is_rel)?;
-                self.cursor = p;
-                self.start_point = p;
-                self.last_control_point = None;
-                Ok(Command::Move { x: p.x, y: p.y })
-            }
-            'L' => {
-                let p = self.get_abs_point(is_rel)?;
-                self.cursor = p;
-                self.last_control_point = None;
-                Ok(Command::Line { x: p.x, y: p.y })
-            }
-            'H' => {
-                let mut x = self.next_num()?;
-                if is_rel {
-                    x += self.cursor.x;
-                }
-                self.cursor.x = x;
-                self.last_control_point = None;
-                Ok(Command::Horizontal { x })
-            }
-            'V' => {
-                let mut y = self.next_num()?;
-                if is_rel {
-                    y += self.cursor.y;
-                }
-                self.cursor.y = y;
-                self.last_control_point = None;
-                Ok(Command::Vertical { y })
-            }
-            'C' => {
-                let p1 = self.get_abs_point(is_rel)?;
-                let p2 = self.get_abs_point(is_rel)?;
-                let p = self.get_abs_point(is_rel)?;
-                self.last_control_point = Some(p2); // CP2 is used for next 'S' reflection
-                self.cursor = p;
-                Ok(Command::Cubic {
-                    x1: p1.x,
-                    y1: p1.y,
-                    x2: p2.x,
-                    y2: p2.y,
-                    x: p.x,
-                    y: p.y,
-                })
-            }
-            'S' => {
-                let p2 = self.get_abs_point(is_rel)?;
-                let p = self.get_abs_point(is_rel)?;
-                self.last_control_point = Some(p2);
-                self.cursor = p;
-                Ok(Command::SmoothCubic {
-                    x2: p2.x,
-                    y2: p2.y,
-                    x: p.x,
-                    y: p.y,
-                })
-            }
-            'Q' => {
-                let p1 = self.get_abs_point(is_rel)?;
-                let p = self.get_abs_point(is_rel)?;
-                self.last_control_point = Some(p1); // CP1 is used for next 'T' reflection
-                self.cursor = p;
-                Ok(Command::Quadratic {
-                    x1: p1.x,
-                    y1: p1.y,
-                    x: p.x,
-                    y: p.y,
-                })
+    /// Apply `matrix` to the commands on the fly, see [`Transformed`].
+    pub fn transform(self, matrix: &Matrix) -> Transformed<'a> {
+        Transformed::new(self, matrix)
+    }
+}
+
+impl Iterator for PathParser<'_> {
+    type Item = Result<Command, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            if self.parser.peek().is_none() {
+                self.done = true;
+                let pos = self.parser.position();
+                return Some(Err(ParserError::EndOfStream { pos }));
             }
-            'T' => {
-                let _p1 = self.reflect_control_point();
-                let p = self.get_abs_point(is_rel)?;
-                // The reflected point is technically the control point for this segment
-                self.last_control_point = Some(self.reflect_control_point());
-                self.cursor = p;
-                Ok(Command::SmoothQuadratic { x: p.x, y: p.y })
+        }
+        match self.parser.next_command() {
+            Some(Ok(raw)) => Some(Ok(self.cursor.absolute(raw.letter, raw.args()))),
+            Some(Err(e)) => {
+                self.done = true;
+                Some(Err(e))
             }
-            'A' => {
-                let rx = self.next_num()?;
-                let ry = self.next_num()?;
-                let rot = self.next_num()?;
-                let large = self.next_num()? != 0.0;
-                let sweep = self.next_num()? != 0.0;
-                let p = self.get_abs_point(is_rel)?;
-                self.cursor = p;
-                self.last_control_point = None;
-                Ok(Command::Arc {
-                    rx,
-                    ry,
-                    x_axis_rotation: rot,
-                    large_arc_flag: large,
-                    sweep_flag: sweep,
-                    x: p.x,
-                    y: p.y,
-                })
+            None => {
+                self.done = true;
+                None
             }
-            'Z' => {
-                self.cursor = self.start_point;
-                self.last_control_point = None;
+        }
+    }
+}
 
-                // Check if a number follows Z illegally
-                if let Some(Ok(Token::Number(n))) = self.lexer.peek() {
-                    return Err(ParserError::UnexpectedToken(Token::Number(*n)));
-                }
-                Ok(Command::Close)
+impl FusedIterator for PathParser<'_> {}
+
+pub(crate) struct Parser<'a> {
+    lexer: Lexer<'a>,
+    peeked: Option<Spanned>,
+    // Command letter used for implicit repeats
+    current: Option<char>,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self {
+            lexer: Lexer::new(input),
+            peeked: None,
+            current: None,
+        }
+    }
+
+    /// Position just past the last consumed token.
+    pub(crate) fn position(&self) -> Position {
+        self.lexer.position()
+    }
+
+    fn lex(&mut self) -> Option<Spanned> {
+        let token = self.lexer.next()?;
+        Some(token.map(|(pos, token)| (pos, token, self.lexer.position().offset)))
+    }
+
+    fn lex_flag(&mut self) -> Option<Spanned> {
+        let token = self.lexer.next_flag()?;
+        Some(token.map(|(pos, token)| (pos, token, self.lexer.position().offset)))
+    }
+
+    fn peek(&mut self) -> Option<&Spanned> {
+        if self.peeked.is_none() {
+            self.peeked = self.lex();
+        }
+        self.peeked.as_ref()
+    }
+
+    fn next_token(&mut self) -> Option<Spanned> {
+        self.peeked.take().or_else(|| self.lex())
+    }
+
+    pub(crate) fn parse(&mut self) -> Result<Vec<Command>, ParserError> {
+        if self.peek().is_none() {
+            return Err(ParserError::EndOfStream {
+                pos: self.position(),
+            });
+        }
+
+        let mut commands = Vec::new();
+        self.parse_into(&mut commands)?;
+        Ok(commands)
+    }
+
+    /// Parse following the SVG 2 error handling rules: everything up to, but
+    /// not including, the command containing the first error is kept.
+    pub(crate) fn parse_lenient(&mut self) -> (Vec<Command>, Option<ParserError>) {
+        let mut commands = Vec::new();
+
+        // Path data that does not begin with a moveto renders nothing
+        match self.peek() {
+            None => return (commands, None),
+            Some(Ok((pos, Token::Command(c), _))) if !c.eq_ignore_ascii_case(&'M') => {
+                let pos = *pos;
+                return (commands, Some(ParserError::NoStartingCommand { pos }));
             }
-            _ => Err(ParserError::LexerErr(LexerError::InvalidCommand(c))),
+            _ => {}
         }
+
+        let err = self.parse_into(&mut commands).err();
+        (commands, err)
     }
 
-    /// Helper to fetch the next two numbers and return an absolute Point
-    fn get_abs_point(&mut self, is_rel: bool) -> Result<Point, ParserError> {
-        let mut x = self.next_num()?;
-        let mut y = self.next_num()?;
-        if is_rel {
-            x += self.cursor.x;
-            y += self.cursor.y;
+    /// Appends every complete command to `commands`, stopping at the first error.
+    fn parse_into(&mut self, commands: &mut Vec<Command>) -> Result<(), ParserError> {
+        let mut cursor = Cursor::new();
+        while let Some(raw) = self.next_command() {
+            let raw = raw?;
+            commands.push(cursor.absolute(raw.letter, raw.args()));
         }
-        Ok(Point { x, y })
+        Ok(())
     }
 
-    /// Calculates the reflection of the previous control point.
-    /// If the previous command was not a curve, it returns the current cursor.
-    fn reflect_control_point(&self) -> Point {
-        match self.last_control_point {
-            Some(last) => Point {
-                x: 2.0 * self.cursor.x - last.x,
-                y: 2.0 * self.cursor.y - last.y,
-            },
-            None => self.cursor,
+    /// Reads the next command together with its arguments.
+    pub(crate) fn next_command(&mut self) -> Option<Result<RawCommand, ParserError>> {
+        let (pos, token, end) = match self.next_token()? {
+            Ok(token) => token,
+            Err(e) => return Some(Err(e.into())),
+        };
+
+        let (letter, implicit) = match (token, self.current) {
+            (Token::Command(c), None) if !c.eq_ignore_ascii_case(&'M') => {
+                return Some(Err(ParserError::NoStartingCommand { pos }));
+            }
+            (Token::Command(c), _) => (c, false),
+            // Numbers are consumed together with their command, so a number
+            // without one can only be a leading one ("6" or "6 M 0 0")
+            (Token::Number(_), None) => {
+                return Some(Err(ParserError::NoStartingCommand { pos }));
+            }
+            (Token::Number(n), Some(c)) => {
+                if c.eq_ignore_ascii_case(&'Z') {
+                    return Some(Err(ParserError::UnexpectedToken {
+                        token: Token::Number(n),
+                        pos,
+                    }));
+                }
+                // Put the number back, it is the first argument
+                self.peeked = Some(Ok((pos, Token::Number(n), end)));
+                (c, true)
+            }
+        };
+
+        // A moveto followed by bare numbers continues as lineto
+        self.current = Some(match letter {
+            'M' => 'L',
+            'm' => 'l',
+            c => c,
+        });
+
+        let mut raw = RawCommand {
+            letter,
+            implicit,
+            start: pos.offset,
+            args: [0.0; 7],
+            spans: [(0, 0); 7],
+        };
+        if let Err(e) = self.read_args(&mut raw) {
+            return Some(Err(e));
         }
+        Some(Ok(raw))
     }
 
-    /// Pulls the next number from the lexer or returns an error
-    fn next_num(&mut self) -> Result<f64, ParserError> {
-        match self.lexer.next() {
-            Some(Ok(Token::Number(n))) => Ok(n),
-            Some(Ok(Token::Command(c))) => Err(ParserError::UnexpectedToken(Token::Command(c))),
-            Some(Err(e)) => Err(ParserError::LexerErr(e)),
-            None => Err(ParserError::EndOfStream),
+    /// Pulls the arguments of command `raw` from the lexer
+    fn read_args(&mut self, raw: &mut RawCommand) -> Result<(), ParserError> {
+        let expected = arg_count(raw.letter);
+
+        let is_arc = raw.letter.eq_ignore_ascii_case(&'A');
+
+        for found in 0..expected {
+            if is_arc && (found == 3 || found == 4) && self.peeked.is_none() {
+                self.peeked = self.lex_flag();
+            }
+            let pos = match self.peek() {
+                Some(Ok((pos, Token::Number(n), end))) => {
+                    raw.args[found] = *n;
+                    raw.spans[found] = (pos.offset, *end);
+                    self.next_token();
+                    continue;
+                }
+                Some(Ok((pos, Token::Command(_), _))) => *pos,
+                Some(Err(_)) => return Err(self.next_token().unwrap().unwrap_err().into()),
+                None => self.position(),
+            };
+            return Err(ParserError::MissingArgument {
+                cmd: raw.letter,
+                expected,
+                found,
+                pos,
+            });
         }
+        Ok(())
     }
 }
 
@@ -425,6 +580,7 @@ mod t {
             ("M 0 0 H 10 V 8Z", "M 0 0 H 10 V 8 Z"),
             ("M5,7h10v-13z", "M 5 7 H 15 V -6 Z"),
             ("M5,7l-3,3", "M 5 7 L 2 10"),
+            ("M 10.5.5", "M 10.5 0.5"),
         ];
         for (s, out) in test_data {
             let mut p = Parser::new(s);
@@ -443,9 +599,8 @@ mod t {
             "M",
             "5",
             "MM 0 5 L 6 9",
-            //"M 10.5.5",
             "M 0 0 L 1e2e3",
-            //"L 7 5",
+            "L 7 5",
             "M 10 @ 20",
             "M -.e10",
             "M 9,5 h 20 Z 0",
@@ -467,4 +622,46 @@ mod t {
             assert!(res.is_err());
         }
     }
+
+    #[test]
+    fn error_messages() {
+        let test_data = [
+            ("", "line 1, col 1: empty path data"),
+            (
+                "5 M 0 0",
+                "line 1, col 1: path data must start with a moveto command",
+            ),
+            (
+                "M 0 0\nL 10 10\n  L 5",
+                "line 3, col 6: expected 2 numbers after 'L', found 1",
+            ),
+            (
+                "M 0 0 a 1 1 0 0 1 h 5",
+                "line 1, col 19: expected 7 numbers after 'a', found 5",
+            ),
+            (
+                "M 0 0 V",
+                "line 1, col 8: expected 1 number after 'V', found 0",
+            ),
+            ("M 9,5 h 20 Z 0", "line 1, col 14: unexpected number 0"),
+            ("M 10 @ 20", "line 1, col 6: unexpected character '@'"),
+            ("M 5 5 X 7 3", "line 1, col 7: invalid command 'X'"),
+            ("M -.e10", "line 1, col 3: invalid number '-.e10'"),
+            ("M 1,,2", "line 1, col 5: unexpected character ','"),
+            ("M 1 2, L 3 4", "line 1, col 6: unexpected character ','"),
+            (
+                "M 0 0 a 1 1 0 2 0 5 5",
+                "line 1, col 15: invalid arc flag '2'",
+            ),
+        ];
+        for (s, msg) in test_data {
+            let err = Parser::new(s).parse().unwrap_err();
+            assert_eq!(err.to_string(), msg);
+        }
+
+        let s = "M 0 0\nL 10 10 L 5 Z";
+        let err = Parser::new(s).parse().unwrap_err();
+        assert_eq!(err.position().offset, 18);
+        assert_eq!(err.snippet(s), "L 10 10 L 5 Z\n            ^");
+    }
 }
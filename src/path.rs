@@ -1,11 +1,19 @@
 use std::fmt;
 
+use crate::area;
+use crate::boolean::{self, BooleanOp};
+use crate::flatten::{self, Polylines, check_tolerance};
+use crate::hit::{self, FillRule, NearestPoint};
+use crate::intersect::{self, Intersection};
 use crate::matrix::transform_path;
 use crate::parser::{Parser, ParserError};
 use crate::reverse::reverse_path;
+use crate::segment::{self, Segment};
 use crate::simplify::simplify;
+use crate::stroke::{self, LineJoin, StrokeStyle};
 use crate::utils;
-use crate::{BBox, Command, Matrix, Rect};
+use crate::writer::{self, WriteOptions};
+use crate::{BBox, Command, Matrix, Point, Rect};
 
 // --- Path
 
@@ -22,6 +30,17 @@ pub fn parse(s: &str) -> Result<Path, ParserError> {
     Ok(Path { commands })
 }
 
+/// Parse SVG Path string the way browsers do: render up to the first error.
+///
+/// Returns the commands preceding the command that contains the first error,
+/// together with that error. Following the SVG 2 rules, path data that does not
+/// start with a moveto yields an empty path, and empty path data is not an error.
+pub fn parse_lenient(s: &str) -> (Path, Option<ParserError>) {
+    let mut p = Parser::new(s);
+    let (commands, err) = p.parse_lenient();
+    (Path { commands }, err)
+}
+
 impl Path {
     pub fn new(cmds: &[Command]) -> Self {
         Self {
@@ -41,6 +60,28 @@ impl Path {
         SimplePath { commands }
     }
 
+    /// Exact bounding box, with arcs and quadratics measured analytically.
+    /// `None` for an empty path.
+    pub fn bbox(&self) -> Option<BBox> {
+        crate::bbox::bbox(&self.commands, false)
+    }
+
+    /// Box enclosing every point and control point, and the full ellipse of
+    /// arcs. Quicker to compute than [`bbox`](Self::bbox) but may be larger.
+    pub fn loose_bbox(&self) -> Option<BBox> {
+        crate::bbox::bbox(&self.commands, true)
+    }
+
+    /// Apply a transformation matrix.
+    ///
+    /// Arcs stay arcs with new radii, rotation and sweep. `H` and `V` become
+    /// `L` only when the matrix rotates or skews them.
+    #[must_use]
+    pub fn transform(&self, m: &Matrix) -> Self {
+        let commands = transform_path(&self.commands, m);
+        Self { commands }
+    }
+
     /// Split this path into individual subpaths.
     #[must_use]
     pub fn split(&self) -> Vec<Path> {
@@ -53,19 +94,16 @@ impl Path {
     pub fn subpaths_count(&self) -> usize {
         utils::split_count(&self.commands)
     }
+
+    /// Format this path using `opts`.
+    pub fn to_string_with(&self, opts: &WriteOptions) -> String {
+        writer::write_path(&self.commands, opts)
+    }
 }
 
 impl fmt::Display for Path {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let last = self.commands.len() - 1;
-        for (i, cmd) in self.commands.iter().enumerate() {
-            if i == last {
-                write!(f, "{cmd}")?
-            } else {
-                write!(f, "{cmd} ")?
-            }
-        }
-        Ok(())
+        f.write_str(&self.to_string_with(&WriteOptions::default()))
     }
 }
 
@@ -140,9 +178,15 @@ impl SimplePath {
         self.commands.iter().map(|cmd| cmd.into())
     }
 
-    /// Path bounding box
-    pub fn bbox(&self) -> BBox {
-        crate::bbox::bbox(&self.commands).unwrap()
+    /// Path bounding box, `None` for an empty path
+    pub fn bbox(&self) -> Option<BBox> {
+        crate::bbox::bbox(&self.commands, false)
+    }
+
+    /// Box enclosing every point and control point, quicker to compute than
+    /// [`bbox`](Self::bbox) but may be larger.
+    pub fn loose_bbox(&self) -> Option<BBox> {
+        crate::bbox::bbox(&self.commands, true)
     }
 
     /// Apply a transformation matrix
@@ -155,7 +199,9 @@ impl SimplePath {
     /// Fit this path into target rectangle
     #[must_use]
     pub fn fit(&self, target: &Rect, keep_aspect_ratio: bool, centered: bool) -> Self {
-        let bb = self.bbox();
+        let Some(bb) = self.bbox() else {
+            return self.clone();
+        };
         let src: Rect = (&bb).into();
         let m = utils::inbox_matrix(&src, target, keep_aspect_ratio, centered);
         self.transform(&m)
@@ -190,18 +236,492 @@ impl SimplePath {
         }
         true
     }
-}
 
-impl fmt::Display for SimplePath {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let last = self.commands.len() - 1;
-        for (i, cmd) in self.commands.iter().enumerate() {
-            if i == last {
-                write!(f, "{cmd}")?
+    /// Format this path using `opts`.
+    pub fn to_string_with(&self, opts: &WriteOptions) -> String {
+        writer::write_path(&self.commands, opts)
+    }
+
+    /// Replace every cubic with lines that deviate at most `tolerance` from it.
+    ///
+    /// # Panics
+    ///
+    /// If `tolerance` is not a positive finite number.
+    #[must_use]
+    pub fn flatten(&self, tolerance: f64) -> Self {
+        check_tolerance(tolerance);
+        let commands = flatten::flatten(&self.commands, tolerance);
+        Self { commands }
+    }
+
+    /// Lazily flatten each subpath into a polyline, see [`flatten`](Self::flatten).
+    ///
+    /// # Panics
+    ///
+    /// If `tolerance` is not a positive finite number.
+    pub fn polylines(&self, tolerance: f64) -> Polylines<'_> {
+        check_tolerance(tolerance);
+        Polylines::new(&self.commands, tolerance)
+    }
+
+    /// Signed area enclosed by the path, open subpaths are closed with a
+    /// straight line.
+    ///
+    /// The area is positive for subpaths running clockwise on screen, with the
+    /// y axis pointing down. Subpaths wound in opposite directions cancel
+    /// each other out.
+    pub fn area(&self) -> f64 {
+        area::moments(&self.commands).area
+    }
+
+    /// Check if the path runs clockwise on screen, see [`area`](Self::area).
+    ///
+    /// Use [`split`](Self::split) to check each subpath on its own.
+    pub fn is_clockwise(&self) -> bool {
+        self.area() > 0.0
+    }
+
+    /// Center of mass of the enclosed area, `None` if the area is zero.
+    pub fn centroid(&self) -> Option<Point> {
+        let m = area::moments(&self.commands);
+        if m.area.abs() < 1e-12 {
+            return None;
+        }
+        Some(Point {
+            x: m.mx / m.area,
+            y: m.my / m.area,
+        })
+    }
+
+    /// Check if the point `(x, y)` lies inside the filled path.
+    ///
+    /// Open subpaths are closed with a straight line, like a renderer filling
+    /// them does. Points exactly on the outline may go either way.
+    pub fn contains(&self, x: f64, y: f64, rule: FillRule) -> bool {
+        hit::contains(&self.commands, x, y, rule)
+    }
+
+    /// Closest point on the outline to `(x, y)`, `None` for paths without segments.
+    pub fn nearest_point(&self, x: f64, y: f64) -> Option<NearestPoint> {
+        hit::nearest(&self.commands, x, y, f64::INFINITY)
+    }
+
+    /// Check if the point `(x, y)` lies on the outline stroked with `width`.
+    ///
+    /// Caps and joins are treated as round.
+    pub fn stroke_contains(&self, x: f64, y: f64, width: f64) -> bool {
+        hit::nearest(&self.commands, x, y, width / 2.0).is_some()
+    }
+
+    /// Points where the outlines of `self` and `other` cross.
+    ///
+    /// `index_a` and `t_a` refer to `self`, `index_b` and `t_b` to `other`.
+    /// Collinear overlapping parts are not reported.
+    pub fn intersections(&self, other: &SimplePath) -> Vec<Intersection> {
+        intersect::intersections(&self.commands, &other.commands)
+    }
+
+    /// Points where the outline crosses itself.
+    ///
+    /// Each crossing is reported once with `index_a <= index_b`. The points
+    /// where consecutive segments join are not crossings.
+    pub fn self_intersections(&self) -> Vec<Intersection> {
+        intersect::self_intersections(&self.commands)
+    }
+
+    /// Combine the filled areas of `self` filled with `rule` and `other`
+    /// filled with `other_rule`.
+    ///
+    /// Curves are kept as cubics, cut where the outlines cross, and collinear
+    /// lines are joined. Outer contours of the result run clockwise on screen
+    /// and holes counter-clockwise, so it fills the same with either fill rule.
+    #[must_use]
+    pub fn boolean(
+        &self,
+        other: &SimplePath,
+        op: BooleanOp,
+        rule: FillRule,
+        other_rule: FillRule,
+    ) -> Self {
+        let commands = boolean::boolean(&self.commands, &other.commands, op, rule, other_rule);
+        Self { commands }
+    }
+
+    /// Area covered by either path, see [`boolean`](Self::boolean).
+    #[must_use]
+    pub fn union(&self, other: &SimplePath) -> Self {
+        self.boolean(
+            other,
+            BooleanOp::Union,
+            FillRule::NonZero,
+            FillRule::NonZero,
+        )
+    }
+
+    /// Area covered by both paths, see [`boolean`](Self::boolean).
+    #[must_use]
+    pub fn intersection(&self, other: &SimplePath) -> Self {
+        self.boolean(
+            other,
+            BooleanOp::Intersection,
+            FillRule::NonZero,
+            FillRule::NonZero,
+        )
+    }
+
+    /// Area of this path not covered by `other`, see [`boolean`](Self::boolean).
+    #[must_use]
+    pub fn difference(&self, other: &SimplePath) -> Self {
+        self.boolean(
+            other,
+            BooleanOp::Difference,
+            FillRule::NonZero,
+            FillRule::NonZero,
+        )
+    }
+
+    /// Area covered by exactly one of the paths, see [`boolean`](Self::boolean).
+    #[must_use]
+    pub fn xor(&self, other: &SimplePath) -> Self {
+        self.boolean(other, BooleanOp::Xor, FillRule::NonZero, FillRule::NonZero)
+    }
+
+    /// Outline of the path stroked with `style`, to be filled with the
+    /// nonzero rule.
+    ///
+    /// Curves are offset with cubics. Subpaths without length get caps
+    /// aligned with the x axis, like SVG renderers draw them.
+    #[must_use]
+    pub fn stroke_to_path(&self, style: &StrokeStyle) -> Self {
+        let commands = stroke::stroke(&self.commands, style);
+        Self { commands }
+    }
+
+    /// Grow the filled area by `distance`, or shrink it for negative values.
+    ///
+    /// Open subpaths are closed first. Corners are shaped by `join`, with the
+    /// SVG default miter limit, and loops at concave corners are removed.
+    #[must_use]
+    pub fn offset(&self, distance: f64, join: LineJoin) -> Self {
+        let mut closed = Vec::with_capacity(self.commands.len() + 1);
+        for sub in utils::split(&self.commands) {
+            let open = sub.last() != Some(&Command::Close);
+            closed.extend(sub);
+            if open {
+                closed.push(Command::Close);
+            }
+        }
+        let closed = Self { commands: closed };
+        if distance == 0.0 {
+            return closed;
+        }
+
+        let band = closed.stroke_to_path(&StrokeStyle {
+            width: 2.0 * distance.abs(),
+            join,
+            ..Default::default()
+        });
+        if distance > 0.0 {
+            closed.union(&band)
+        } else {
+            closed.difference(&band)
+        }
+    }
+
+    /// Drawn segments, each paired with the index of the command drawing it.
+    pub fn segments(&self) -> impl Iterator<Item = (usize, Segment)> {
+        segment::segments(&self.commands)
+    }
+
+    /// Total length of all subpaths.
+    pub fn length(&self) -> f64 {
+        self.length_with_tolerance(segment::TOLERANCE)
+    }
+
+    /// Total length of all subpaths, with curves measured to within `tolerance`.
+    pub fn length_with_tolerance(&self, tolerance: f64) -> f64 {
+        self.segments().map(|(_, seg)| seg.length(tolerance)).sum()
+    }
+
+    /// Point on the segment drawn by command `index` at parameter `t` in `[0, 1]`.
+    ///
+    /// Returns `None` if that command does not draw a segment.
+    pub fn point_at(&self, index: usize, t: f64) -> Option<Point> {
+        let (_, seg) = self.segments().find(|(i, _)| *i == index)?;
+        Some(seg.point_at(t))
+    }
+
+    /// Point at distance `d` along the path, like SVG `getPointAtLength`.
+    ///
+    /// `d` is clamped to the path length. Returns `None` for paths
+    /// without segments.
+    pub fn point_at_length(&self, d: f64) -> Option<Point> {
+        let (seg, t) = self.locate(d)?;
+        Some(seg.point_at(t))
+    }
+
+    /// Unit tangent vector at distance `d` along the path.
+    pub fn tangent_at_length(&self, d: f64) -> Option<Point> {
+        let (seg, t) = self.locate(d)?;
+        seg.tangent_at(t)
+    }
+
+    /// Unit normal vector at distance `d` along the path.
+    pub fn normal_at_length(&self, d: f64) -> Option<Point> {
+        let (seg, t) = self.locate(d)?;
+        seg.normal_at(t)
+    }
+
+    /// Split into the parts before and after distance `d` along the path.
+    ///
+    /// The second part starts with a moveto at the split point.
+    pub fn split_at_length(&self, d: f64) -> (SimplePath, SimplePath) {
+        (self.trim(0.0, d), self.trim(d, f64::INFINITY))
+    }
+
+    /// The part of the path between distances `start` and `end`.
+    ///
+    /// Each subpath piece starts with a moveto. A closepath is kept only if
+    /// its whole subpath lies inside the range, otherwise it becomes a lineto.
+    /// Subpaths without segments are dropped.
+    #[must_use]
+    pub fn trim(&self, start: f64, end: f64) -> Self {
+        let mut commands = Vec::new();
+        self.trim_measured(&self.measure(), start, end, &mut commands);
+        Self { commands }
+    }
+
+    /// Drawn segments with their lengths and where they start along the path.
+    fn measure(&self) -> Vec<Measured> {
+        let mut result = Vec::new();
+        let mut total = 0.0;
+        let mut prev = 0;
+        for (index, seg) in self.segments() {
+            let first = self.commands[prev..index]
+                .iter()
+                .any(|cmd| matches!(cmd, Command::Move { .. }));
+            prev = index + 1;
+            let len = seg.length(segment::TOLERANCE);
+            result.push(Measured {
+                index,
+                seg,
+                first,
+                start: total,
+                len,
+            });
+            total += len;
+        }
+        result
+    }
+
+    /// [`trim`](Self::trim) over `segs`, a tail of [`measure`](Self::measure)
+    /// starting before `start`.
+    fn trim_measured(&self, segs: &[Measured], start: f64, end: f64, commands: &mut Vec<Command>) {
+        // A moveto was emitted for the current subpath
+        let mut pen_down = false;
+        // The range starts at or before the current subpath
+        let mut whole = false;
+
+        for m in segs {
+            if m.start > end {
+                break;
+            }
+            if m.first {
+                pen_down = false;
+                whole = start <= m.start;
+            }
+
+            let (seg_start, seg_end) = (m.start, m.start + m.len);
+            let a = start.max(seg_start);
+            let b = end.min(seg_end);
+            // Empty segments are only kept inside an emitted piece
+            if a > b || (a == b && (m.len > 0.0 || !pen_down)) {
+                continue;
+            }
+
+            let t0 = if a <= seg_start {
+                0.0
             } else {
-                write!(f, "{cmd} ")?
+                m.seg.t_at_length(a - seg_start, segment::TOLERANCE)
+            };
+            let t1 = if b >= seg_end {
+                1.0
+            } else {
+                m.seg.t_at_length(b - seg_start, segment::TOLERANCE)
+            };
+            let piece = m.seg.subsegment(t0, t1);
+            if !pen_down {
+                let p = piece.from();
+                commands.push(Command::Move { x: p.x, y: p.y });
+                pen_down = true;
+            }
+            if self.commands[m.index] == Command::Close && whole && t1 == 1.0 {
+                commands.push(Command::Close);
+            } else {
+                commands.push(piece.to_command());
+            }
+        }
+    }
+
+    /// Cut the path into dashes like SVG `stroke-dasharray` and
+    /// `stroke-dashoffset` do.
+    ///
+    /// An odd number of lengths is repeated to make it even. The pattern
+    /// starts over on each subpath, and on closed subpaths a dash running
+    /// over the start point is kept in one piece. Empty patterns, patterns
+    /// summing to zero or with negative lengths leave the path solid.
+    #[must_use]
+    pub fn dash(&self, pattern: &[f64], offset: f64) -> Self {
+        let mut pattern = pattern.to_vec();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_within(..);
+        }
+        let total: f64 = pattern.iter().sum();
+        if pattern.iter().any(|d| *d < 0.0) || !total.is_finite() || total <= 0.0 {
+            return self.clone();
+        }
+
+        let mut commands = Vec::new();
+        for sub in self.split() {
+            // Measure once and walk the segments along with the dashes
+            let segs = sub.measure();
+            let len: f64 = segs.iter().map(|m| m.len).sum();
+
+            // Walk the pattern from its offset position before the subpath start
+            let mut dashes = Vec::new();
+            let mut pos = -offset.rem_euclid(total);
+            'walk: loop {
+                for (i, d) in pattern.iter().enumerate() {
+                    let (start, end) = (pos, pos + d);
+                    pos = end;
+                    if start > len {
+                        break 'walk;
+                    }
+                    // Dashes only touching an end of the subpath are not drawn
+                    let visible = if *d == 0.0 {
+                        start >= 0.0
+                    } else {
+                        end > 0.0 && start < len
+                    };
+                    if i % 2 == 0 && visible {
+                        dashes.push((start.max(0.0), end.min(len)));
+                    }
+                }
+            }
+
+            // Join the last dash with the first over the start of a closed subpath
+            let mut wrapped = None;
+            if sub.commands.last() == Some(&Command::Close)
+                && dashes.len() > 1
+                && dashes[0].0 == 0.0
+                && dashes[0].1 > 0.0
+                && dashes[dashes.len() - 1].0 < len
+                && dashes[dashes.len() - 1].1 == len
+            {
+                let (_, first_end) = dashes.remove(0);
+                wrapped = Some(first_end);
+            }
+
+            let mut first = 0;
+            for (i, &(start, end)) in dashes.iter().enumerate() {
+                while first + 1 < segs.len() && segs[first].start + segs[first].len < start {
+                    first += 1;
+                }
+                if start == end {
+                    // Empty dashes still get caps when stroked
+                    let p = point_at_measured(&segs[first..], start)
+                        .unwrap_or(Point { x: 0.0, y: 0.0 });
+                    commands.push(Command::Move { x: p.x, y: p.y });
+                    commands.push(Command::Line { x: p.x, y: p.y });
+                    continue;
+                }
+                sub.trim_measured(&segs[first..], start, end, &mut commands);
+                if let Some(first_end) = wrapped
+                    && i == dashes.len() - 1
+                {
+                    let mut head = Vec::new();
+                    sub.trim_measured(&segs, 0.0, first_end, &mut head);
+                    commands.extend(head.into_iter().skip(1));
+                }
             }
         }
-        Ok(())
+        Self { commands }
+    }
+
+    /// Find the segment and parameter at distance `d`, skipping empty segments.
+    fn locate(&self, d: f64) -> Option<(Segment, f64)> {
+        let mut last = None;
+        let mut total = 0.0;
+        for (_, seg) in self.segments() {
+            let len = seg.length(segment::TOLERANCE);
+            if len == 0.0 && last.is_some() {
+                continue;
+            }
+            if d <= total + len {
+                let t = seg.t_at_length(d - total, segment::TOLERANCE);
+                return Some((seg, t));
+            }
+            total += len;
+            last = Some(seg);
+        }
+        last.map(|seg| (seg, 1.0))
+    }
+
+    /// Cumulative lengths for each subpath from [`split`](Self::split).
+    ///
+    /// Entry `i` of a table is the distance from the start of the subpath to
+    /// the end of its command `i`.
+    pub fn cumulative_lengths(&self, tolerance: f64) -> Vec<Vec<f64>> {
+        self.split()
+            .iter()
+            .map(|sp| {
+                let mut table = vec![0.0; sp.commands.len()];
+                let mut total = 0.0;
+                let mut segs = sp.segments().peekable();
+                for (i, entry) in table.iter_mut().enumerate() {
+                    if let Some((_, seg)) = segs.next_if(|(j, _)| *j == i) {
+                        total += seg.length(tolerance);
+                    }
+                    *entry = total;
+                }
+                table
+            })
+            .collect()
+    }
+}
+
+/// A drawn segment of a `SimplePath` with its place along the path.
+struct Measured {
+    /// Index of the command drawing it
+    index: usize,
+    seg: Segment,
+    /// First segment after a moveto
+    first: bool,
+    /// Distance from the path start to the segment start
+    start: f64,
+    len: f64,
+}
+
+/// Point at distance `d` along `segs`, like `SimplePath::point_at_length`.
+fn point_at_measured(segs: &[Measured], d: f64) -> Option<Point> {
+    let mut last = None;
+    for m in segs {
+        if m.len == 0.0 && last.is_some() {
+            continue;
+        }
+        if d <= m.start + m.len {
+            return Some(
+                m.seg
+                    .point_at(m.seg.t_at_length(d - m.start, segment::TOLERANCE)),
+            );
+        }
+        last = Some(&m.seg);
+    }
+    last.map(|seg| seg.point_at(1.0))
+}
+
+impl fmt::Display for SimplePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with(&WriteOptions::default()))
     }
 }
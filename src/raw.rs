@@ -0,0 +1,278 @@
+use std::fmt;
+
+use crate::Path;
+use crate::parser::{Cursor, Parser, ParserError, arg_count};
+
+/// A number together with its original spelling.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawNumber {
+    value: f64,
+    text: String,
+    // Whitespace and commas preceding the number
+    separator: String,
+    // Large arc or sweep flag of an arc
+    flag: bool,
+}
+
+impl RawNumber {
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The number exactly as written in the source.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whitespace and commas written before the number.
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    /// The number is the large arc or sweep flag of an arc.
+    pub fn is_flag(&self) -> bool {
+        self.flag
+    }
+
+    /// Replace the value, the separator before it is kept.
+    ///
+    /// Returns `false` and leaves the number unchanged if `value` is not
+    /// finite, or is a flag other than 0 or 1.
+    #[must_use]
+    pub fn set_value(&mut self, value: f64) -> bool {
+        if !is_valid_value(value, self.flag) {
+            return false;
+        }
+        self.value = value;
+        // Flags must not be written as `-0`
+        self.text = if self.flag {
+            (value as u8).to_string()
+        } else {
+            value.to_string()
+        };
+        true
+    }
+}
+
+fn is_valid_value(value: f64, flag: bool) -> bool {
+    if flag {
+        value == 0.0 || value == 1.0
+    } else {
+        value.is_finite()
+    }
+}
+
+fn is_flag(letter: char, index: usize) -> bool {
+    letter.eq_ignore_ascii_case(&'A') && (index == 3 || index == 4)
+}
+
+/// One command of a [`RawPath`], relative or absolute as written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawSegment {
+    // Whitespace and commas preceding the command letter
+    prefix: String,
+    letter: char,
+    implicit: bool,
+    args: Vec<RawNumber>,
+}
+
+impl RawSegment {
+    /// Create a segment with an explicit command letter.
+    ///
+    /// Returns `None` if `letter` is not a path command, the number of
+    /// arguments does not match it or an argument is not a valid value, see
+    /// [`RawNumber::set_value`].
+    pub fn new(letter: char, args: &[f64]) -> Option<Self> {
+        if !"MLHVCSQTAZ".contains(letter.to_ascii_uppercase()) || arg_count(letter) != args.len() {
+            return None;
+        }
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                let mut n = RawNumber {
+                    value: 0.0,
+                    text: "0".into(),
+                    separator: " ".into(),
+                    flag: is_flag(letter, i),
+                };
+                n.set_value(value).then_some(n)
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            prefix: String::new(),
+            letter,
+            implicit: false,
+            args,
+        })
+    }
+
+    /// Command letter in effect for this segment.
+    ///
+    /// Implicit repeats after a moveto report `L` or `l`.
+    pub fn letter(&self) -> char {
+        self.letter
+    }
+
+    pub fn is_relative(&self) -> bool {
+        self.letter.is_lowercase()
+    }
+
+    /// The command letter was omitted in the source.
+    pub fn is_implicit(&self) -> bool {
+        self.implicit
+    }
+
+    pub fn args(&self) -> &[RawNumber] {
+        &self.args
+    }
+
+    pub fn args_mut(&mut self) -> &mut [RawNumber] {
+        &mut self.args
+    }
+}
+
+/// `RawPath` keeps the path data exactly as written: relative and absolute
+/// commands, implicit repeats, number spelling and separators.
+///
+/// Its `Display` output reproduces the source byte-for-byte as long as no
+/// number was changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawPath {
+    segments: Vec<RawSegment>,
+    // Whitespace and commas after the last command
+    trailing: String,
+}
+
+/// Parse SVG Path string without any conversion.
+pub fn parse_raw(s: &str) -> Result<RawPath, ParserError> {
+    let mut p = Parser::new(s);
+    let mut segments = Vec::new();
+    let mut last_end = 0;
+
+    while let Some(raw) = p.next_command() {
+        let raw = raw?;
+
+        let prefix = if raw.implicit {
+            String::new()
+        } else {
+            let prefix = s[last_end..raw.start].to_string();
+            last_end = raw.start + 1;
+            prefix
+        };
+
+        let args = raw
+            .args()
+            .iter()
+            .zip(raw.spans())
+            .enumerate()
+            .map(|(i, (&value, &(start, end)))| {
+                let n = RawNumber {
+                    value,
+                    text: s[start..end].to_string(),
+                    separator: s[last_end..start].to_string(),
+                    flag: is_flag(raw.letter, i),
+                };
+                last_end = end;
+                n
+            })
+            .collect();
+
+        segments.push(RawSegment {
+            prefix,
+            letter: raw.letter,
+            implicit: raw.implicit,
+            args,
+        });
+    }
+
+    if segments.is_empty() {
+        return Err(ParserError::EndOfStream { pos: p.position() });
+    }
+
+    Ok(RawPath {
+        segments,
+        trailing: s[last_end..].to_string(),
+    })
+}
+
+impl RawPath {
+    pub fn segments(&self) -> impl Iterator<Item = &RawSegment> {
+        self.segments.iter()
+    }
+
+    /// Segments for editing, inserting or removing.
+    ///
+    /// An implicit segment whose letter no longer follows from the segment
+    /// before it is written with its letter.
+    pub fn segments_mut(&mut self) -> &mut Vec<RawSegment> {
+        &mut self.segments
+    }
+
+    /// Convert into a `Path` with absolute coordinates.
+    pub fn to_path(&self) -> Path {
+        let mut cursor = Cursor::new();
+        let mut args = Vec::with_capacity(7);
+        let commands: Vec<_> = self
+            .segments
+            .iter()
+            .map(|seg| {
+                args.clear();
+                args.extend(seg.args.iter().map(|n| n.value));
+                cursor.absolute(seg.letter, &args)
+            })
+            .collect();
+        Path::new(&commands)
+    }
+}
+
+/// Command letter an implicit segment after `prev` repeats.
+fn implied_letter(prev: Option<&RawSegment>) -> Option<char> {
+    match prev?.letter {
+        'Z' | 'z' => None,
+        'M' => Some('L'),
+        'm' => Some('l'),
+        letter => Some(letter),
+    }
+}
+
+/// Two numbers written without a separator must still lex as two numbers.
+fn needs_space(prev: &str, next: &str) -> bool {
+    match next.as_bytes().first() {
+        Some(b'-' | b'+') => false,
+        Some(b'.') => !prev.contains(['.', 'e', 'E']),
+        _ => true,
+    }
+}
+
+impl fmt::Display for RawPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut prev: Option<&str> = None;
+        let mut prev_seg = None;
+        for seg in &self.segments {
+            if !seg.implicit {
+                write!(f, "{}{}", seg.prefix, seg.letter)?;
+                prev = None;
+            } else if implied_letter(prev_seg) != Some(seg.letter) {
+                // The segment that implied it was edited or removed
+                write!(f, "{}", seg.letter)?;
+                prev = None;
+            }
+            prev_seg = Some(seg);
+            let is_arc = seg.letter.eq_ignore_ascii_case(&'A');
+            for (i, n) in seg.args.iter().enumerate() {
+                // Arc flags are single digits and need no separator after them
+                let after_flag = is_arc && (i == 4 || i == 5);
+                if n.separator.is_empty()
+                    && !after_flag
+                    && prev.is_some_and(|p| needs_space(p, &n.text))
+                {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}{}", n.separator, n.text)?;
+                prev = Some(&n.text);
+            }
+        }
+        write!(f, "{}", self.trailing)
+    }
+}
@@ -67,17 +67,16 @@ fn reverse_subpath(cmds: &[&Command]) -> Vec<Command> {
         let prev_pt = points[i - 1]; // The 'start' of this command in forward direction
 
         match *cmd {
-            Command::Line { .. } | Command::Close => {
-                // Eliminate the 'L x y' if we are already at (x, y)
+            // Eliminate the 'L x y' if we are already at (x, y)
+            Command::Line { .. } | Command::Close
                 if (prev_pt.x - current_pos.x).abs() > 1e-9
-                    || (prev_pt.y - current_pos.y).abs() > 1e-9
-                {
-                    reversed.push(Command::Line {
-                        x: prev_pt.x,
-                        y: prev_pt.y,
-                    });
-                    current_pos = prev_pt;
-                }
+                    || (prev_pt.y - current_pos.y).abs() > 1e-9 =>
+            {
+                reversed.push(Command::Line {
+                    x: prev_pt.x,
+                    y: prev_pt.y,
+                });
+                current_pos = prev_pt;
             }
             Command::Cubic { x1, y1, x2, y2, .. } => {
                 reversed.push(Command::Cubic {
@@ -0,0 +1,151 @@
+/// Real roots of `a t^2 + b t + c`.
+pub(crate) fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let mut roots = Vec::new();
+    if a.abs() < 1e-9 {
+        // Quadratic reduces to linear
+        if b.abs() > 1e-9 {
+            roots.push(-c / b);
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant >= 0.0 {
+            let sqrt_d = discriminant.sqrt();
+            roots.push((-b + sqrt_d) / (2.0 * a));
+            roots.push((-b - sqrt_d) / (2.0 * a));
+        }
+    }
+    roots
+}
+
+/// Parameters in `(0, 1)` where one coordinate of a cubic Bezier has a local
+/// extremum, sorted.
+pub(crate) fn cubic_extrema(p0: f64, p1: f64, p2: f64, p3: f64) -> Vec<f64> {
+    // Derivative of cubic Bezier: at^2 + bt + c = 0
+    let a = 3.0 * (-p0 + 3.0 * p1 - 3.0 * p2 + p3);
+    let b = 6.0 * (p0 - 2.0 * p1 + p2);
+    let c = 3.0 * (p1 - p0);
+
+    let mut roots: Vec<_> = quadratic(a, b, c)
+        .into_iter()
+        .filter(|t| *t > 0.0 && *t < 1.0)
+        .collect();
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Value of the polynomial with `coeffs` in ascending powers at `t`.
+pub(crate) fn eval(coeffs: &[f64], t: f64) -> f64 {
+    coeffs.iter().rev().fold(0.0, |acc, c| acc * t + c)
+}
+
+/// Real roots in `[lo, hi]` of the polynomial with `coeffs` in ascending
+/// powers, sorted.
+///
+/// Roots of the derivative split the interval into monotone pieces, each of
+/// which holds at most one root found by bisection.
+pub(crate) fn polynomial(coeffs: &[f64], lo: f64, hi: f64) -> Vec<f64> {
+    // Drop vanishing leading coefficients
+    let scale = coeffs.iter().fold(0.0f64, |m, c| m.max(c.abs()));
+    let Some(degree) = coeffs.iter().rposition(|c| c.abs() > scale * 1e-12) else {
+        return Vec::new();
+    };
+    let coeffs = &coeffs[..=degree];
+
+    if degree == 0 {
+        return Vec::new();
+    }
+    if degree == 1 {
+        let t = -coeffs[0] / coeffs[1];
+        return if (lo..=hi).contains(&t) {
+            vec![t]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let derivative: Vec<_> = coeffs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * i as f64)
+        .collect();
+    let mut bounds = vec![lo];
+    bounds.extend(polynomial(&derivative, lo, hi));
+    bounds.push(hi);
+
+    let f = |t| eval(coeffs, t);
+    let mut roots: Vec<f64> = Vec::new();
+    for w in bounds.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let (fa, fb) = (f(a), f(b));
+        let t = if fa == 0.0 {
+            a
+        } else if fb == 0.0 {
+            b
+        } else if (fa < 0.0) != (fb < 0.0) {
+            bisect(f, a, b)
+        } else {
+            continue;
+        };
+        if roots.last() != Some(&t) {
+            roots.push(t);
+        }
+    }
+    roots
+}
+
+/// Root of `f` in `[lo, hi]`, where `f(lo)` and `f(hi)` differ in sign.
+pub(crate) fn bisect(f: impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let f_lo = f(lo);
+    if f_lo == 0.0 {
+        return lo;
+    }
+    let lo_negative = f_lo < 0.0;
+    for _ in 0..64 {
+        let mid = (lo + hi) / 2.0;
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if (f(mid) < 0.0) == lo_negative {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn roots() {
+        let mut r = quadratic(1.0, -3.0, 2.0);
+        r.sort_by(f64::total_cmp);
+        assert_eq!(r, [1.0, 2.0]);
+        assert_eq!(quadratic(0.0, 2.0, -1.0), [0.5]);
+        assert!(quadratic(1.0, 0.0, 1.0).is_empty());
+
+        assert_eq!(cubic_extrema(0.0, 1.0, 1.0, 0.0), [0.5]);
+        assert!(cubic_extrema(0.0, 1.0, 2.0, 3.0).is_empty());
+
+        let r = bisect(|t| t * t - 2.0, 0.0, 2.0);
+        assert!((r - 2f64.sqrt()).abs() < 1e-15);
+        assert_eq!(bisect(|t| t - 1.0, 1.0, 3.0), 1.0);
+
+        // (t - 0.1)(t - 0.5)(t - 0.9)(t - 2) expanded
+        let coeffs = [0.09, -1.225, 3.59, -3.5, 1.0];
+        let r = polynomial(&coeffs, 0.0, 1.0);
+        assert_eq!(r.len(), 3);
+        for (a, b) in r.iter().zip([0.1, 0.5, 0.9]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        // Double root
+        let r = polynomial(&[0.25, -1.0, 1.0], 0.0, 1.0);
+        assert_eq!(r.len(), 1);
+        assert!((r[0] - 0.5).abs() < 1e-6);
+        assert!(polynomial(&[1.0, 0.0, 1.0], -5.0, 5.0).is_empty());
+        assert!(polynomial(&[3.0], 0.0, 1.0).is_empty());
+    }
+}
@@ -0,0 +1,479 @@
+use crate::{BBox, Command, Point};
+
+/// Default tolerance for numeric approximations.
+pub(crate) const TOLERANCE: f64 = 1e-9;
+
+/// A single drawn piece of a `SimplePath` with its start point resolved.
+///
+/// `Close` commands produce a `Line` back to the start of the subpath.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    Line {
+        from: Point,
+        to: Point,
+    },
+    Cubic {
+        from: Point,
+        ctrl1: Point,
+        ctrl2: Point,
+        to: Point,
+    },
+}
+
+impl Segment {
+    pub fn from(&self) -> Point {
+        match *self {
+            Segment::Line { from, .. } | Segment::Cubic { from, .. } => from,
+        }
+    }
+
+    pub fn to(&self) -> Point {
+        match *self {
+            Segment::Line { to, .. } | Segment::Cubic { to, .. } => to,
+        }
+    }
+
+    /// Point at parameter `t` in `[0, 1]`.
+    pub fn point_at(&self, t: f64) -> Point {
+        match *self {
+            Segment::Line { from, to } => Point {
+                x: from.x + (to.x - from.x) * t,
+                y: from.y + (to.y - from.y) * t,
+            },
+            Segment::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => Point {
+                x: cubic_at(from.x, ctrl1.x, ctrl2.x, to.x, t),
+                y: cubic_at(from.y, ctrl1.y, ctrl2.y, to.y, t),
+            },
+        }
+    }
+
+    /// Unit tangent vector at parameter `t`, `None` if the segment has no length.
+    pub fn tangent_at(&self, t: f64) -> Option<Point> {
+        let mut d = self.derivative(t);
+        if d.x.hypot(d.y) < 1e-12 {
+            // Control point on top of an end point, look a little further
+            let a = self.point_at((t - 1e-6).max(0.0));
+            let b = self.point_at((t + 1e-6).min(1.0));
+            d = Point {
+                x: b.x - a.x,
+                y: b.y - a.y,
+            };
+        }
+        let len = d.x.hypot(d.y);
+        if len < 1e-12 {
+            return None;
+        }
+        Some(Point {
+            x: d.x / len,
+            y: d.y / len,
+        })
+    }
+
+    /// Unit normal vector at parameter `t`, the tangent rotated 90 degrees clockwise
+    /// on screen (toward positive y for a segment heading in positive x).
+    pub fn normal_at(&self, t: f64) -> Option<Point> {
+        self.tangent_at(t).map(|d| Point { x: -d.y, y: d.x })
+    }
+
+    /// Exact bounding box of the segment.
+    pub fn bbox(&self) -> BBox {
+        let mut bb = BBox::new();
+        match *self {
+            Segment::Line { from, to } => {
+                bb.add_point(from.x, from.y);
+                bb.add_point(to.x, to.y);
+            }
+            Segment::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => bb.add_cubic(from, ctrl1, ctrl2, to),
+        }
+        bb
+    }
+
+    /// Split at parameter `t` using de Casteljau's algorithm.
+    pub fn split(&self, t: f64) -> (Segment, Segment) {
+        let mid = self.point_at(t);
+        match *self {
+            Segment::Line { from, to } => (
+                Segment::Line { from, to: mid },
+                Segment::Line { from: mid, to },
+            ),
+            Segment::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let lerp = |a: Point, b: Point| Point {
+                    x: a.x + (b.x - a.x) * t,
+                    y: a.y + (b.y - a.y) * t,
+                };
+                let p01 = lerp(from, ctrl1);
+                let p12 = lerp(ctrl1, ctrl2);
+                let p23 = lerp(ctrl2, to);
+                let p012 = lerp(p01, p12);
+                let p123 = lerp(p12, p23);
+                (
+                    Segment::Cubic {
+                        from,
+                        ctrl1: p01,
+                        ctrl2: p012,
+                        to: mid,
+                    },
+                    Segment::Cubic {
+                        from: mid,
+                        ctrl1: p123,
+                        ctrl2: p23,
+                        to,
+                    },
+                )
+            }
+        }
+    }
+
+    /// The part of the segment between parameters `t0` and `t1`.
+    pub fn subsegment(&self, t0: f64, t1: f64) -> Segment {
+        let head = if t1 >= 1.0 { *self } else { self.split(t1).0 };
+        if t0 <= 0.0 {
+            return head;
+        }
+        // Rescale t0 into the parameter range of the head
+        head.split(t0 / t1).1
+    }
+
+    /// Coefficients of `x(t)` and `y(t)` in ascending powers of `t`.
+    pub(crate) fn power_coeffs(&self) -> ([f64; 4], [f64; 4]) {
+        let (from, ctrl1, ctrl2, to) = match *self {
+            Segment::Line { from, to } => {
+                return (
+                    [from.x, to.x - from.x, 0.0, 0.0],
+                    [from.y, to.y - from.y, 0.0, 0.0],
+                );
+            }
+            Segment::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => (from, ctrl1, ctrl2, to),
+        };
+        let power = |p0: f64, p1: f64, p2: f64, p3: f64| {
+            [
+                p0,
+                3.0 * (p1 - p0),
+                3.0 * (p0 - 2.0 * p1 + p2),
+                -p0 + 3.0 * p1 - 3.0 * p2 + p3,
+            ]
+        };
+        (
+            power(from.x, ctrl1.x, ctrl2.x, to.x),
+            power(from.y, ctrl1.y, ctrl2.y, to.y),
+        )
+    }
+
+    /// Command drawing this segment from its start point.
+    pub(crate) fn to_command(self) -> Command {
+        match self {
+            Segment::Line { to, .. } => Command::Line { x: to.x, y: to.y },
+            Segment::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => Command::Cubic {
+                x1: ctrl1.x,
+                y1: ctrl1.y,
+                x2: ctrl2.x,
+                y2: ctrl2.y,
+                x: to.x,
+                y: to.y,
+            },
+        }
+    }
+
+    /// First derivative at parameter `t`.
+    pub(crate) fn derivative(&self, t: f64) -> Point {
+        match *self {
+            Segment::Line { from, to } => Point {
+                x: to.x - from.x,
+                y: to.y - from.y,
+            },
+            Segment::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let mt = 1.0 - t;
+                let a = 3.0 * mt * mt;
+                let b = 6.0 * mt * t;
+                let c = 3.0 * t * t;
+                Point {
+                    x: a * (ctrl1.x - from.x) + b * (ctrl2.x - ctrl1.x) + c * (to.x - ctrl2.x),
+                    y: a * (ctrl1.y - from.y) + b * (ctrl2.y - ctrl1.y) + c * (to.y - ctrl2.y),
+                }
+            }
+        }
+    }
+
+    /// Arc length, with an absolute error below `tolerance`.
+    pub fn length(&self, tolerance: f64) -> f64 {
+        self.length_between(0.0, 1.0, tolerance)
+    }
+
+    /// Arc length between parameters `t0` and `t1`.
+    pub(crate) fn length_between(&self, t0: f64, t1: f64, tolerance: f64) -> f64 {
+        match *self {
+            Segment::Line { from, to } => (to.x - from.x).hypot(to.y - from.y) * (t1 - t0),
+            Segment::Cubic { .. } => {
+                let speed = |t: f64| {
+                    let d = self.derivative(t);
+                    d.x.hypot(d.y)
+                };
+                let whole = gauss_legendre(&speed, t0, t1);
+                adaptive_gauss_legendre(&speed, t0, t1, whole, tolerance, 24)
+            }
+        }
+    }
+
+    /// Parameter `t` at which the arc length from the start equals `s`.
+    pub(crate) fn t_at_length(&self, s: f64, tolerance: f64) -> f64 {
+        let total = self.length(tolerance);
+        if total <= 0.0 || s <= 0.0 {
+            return 0.0;
+        }
+        if s >= total {
+            return 1.0;
+        }
+        if let Segment::Line { .. } = self {
+            return s / total;
+        }
+
+        // Newton's method, falling back to bisection when a step leaves the bracket
+        let (mut lo, mut hi) = (0.0, 1.0);
+        let mut t = s / total;
+        for _ in 0..64 {
+            let f = self.length_between(0.0, t, tolerance) - s;
+            if f.abs() <= tolerance {
+                break;
+            }
+            if f > 0.0 {
+                hi = t;
+            } else {
+                lo = t;
+            }
+            let d = self.derivative(t);
+            let speed = d.x.hypot(d.y);
+            let next = t - f / speed;
+            t = if speed > 0.0 && next > lo && next < hi {
+                next
+            } else {
+                (lo + hi) / 2.0
+            };
+        }
+        t
+    }
+}
+
+/// One coordinate of a cubic Bezier at parameter `t`.
+pub(crate) fn cubic_at(p0: f64, p1: f64, p2: f64, p3: f64, t: f64) -> f64 {
+    let mt = 1.0 - t;
+    mt * mt * mt * p0 + 3.0 * mt * mt * t * p1 + 3.0 * mt * t * t * p2 + t * t * t * p3
+}
+
+// 5-point Gauss-Legendre abscissae and weights on [-1, 1]
+const GL_NODES: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// Integrate `f` over `[a, b]`, exact for polynomials up to degree 9.
+pub(crate) fn gauss_legendre(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+    let half = (b - a) / 2.0;
+    let mid = (a + b) / 2.0;
+    GL_NODES
+        .iter()
+        .map(|&(x, w)| w * f(mid + half * x))
+        .sum::<f64>()
+        * half
+}
+
+/// Split the interval until both halves agree with the whole within `tolerance`.
+fn adaptive_gauss_legendre(
+    f: &impl Fn(f64) -> f64,
+    a: f64,
+    b: f64,
+    whole: f64,
+    tolerance: f64,
+    depth: u32,
+) -> f64 {
+    let mid = (a + b) / 2.0;
+    let left = gauss_legendre(f, a, mid);
+    let right = gauss_legendre(f, mid, b);
+    if depth == 0 || (left + right - whole).abs() <= tolerance {
+        return left + right;
+    }
+    adaptive_gauss_legendre(f, a, mid, left, tolerance / 2.0, depth - 1)
+        + adaptive_gauss_legendre(f, mid, b, right, tolerance / 2.0, depth - 1)
+}
+
+/// Segments of `commands` together with the index of the command drawing them.
+pub(crate) fn segments(commands: &[Command]) -> impl Iterator<Item = (usize, Segment)> + '_ {
+    let mut cursor = Point { x: 0.0, y: 0.0 };
+    let mut start = cursor;
+
+    commands.iter().enumerate().filter_map(move |(i, cmd)| {
+        let from = cursor;
+        let seg = match *cmd {
+            Command::Move { x, y } => {
+                cursor = Point { x, y };
+                start = cursor;
+                return None;
+            }
+            Command::Line { x, y } => Segment::Line {
+                from,
+                to: Point { x, y },
+            },
+            Command::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => Segment::Cubic {
+                from,
+                ctrl1: Point { x: x1, y: y1 },
+                ctrl2: Point { x: x2, y: y2 },
+                to: Point { x, y },
+            },
+            Command::Close => Segment::Line { from, to: start },
+            _ => return None,
+        };
+        cursor = seg.to();
+        Some((i, seg))
+    })
+}
+
+/// Call `f` with every segment of `commands`, closing open subpaths with a
+/// straight line like a renderer filling them does.
+pub(crate) fn for_each_closed(commands: &[Command], mut f: impl FnMut(&Segment)) {
+    let mut start = Point { x: 0.0, y: 0.0 };
+    let mut cursor = start;
+    let mut prev = 0;
+
+    for (i, seg) in segments(commands) {
+        if commands[prev..i]
+            .iter()
+            .any(|cmd| matches!(cmd, Command::Move { .. }))
+        {
+            f(&Segment::Line {
+                from: cursor,
+                to: start,
+            });
+            start = seg.from();
+        }
+        prev = i + 1;
+        f(&seg);
+        cursor = seg.to();
+    }
+    f(&Segment::Line {
+        from: cursor,
+        to: start,
+    });
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn length() {
+        let p = |x, y| Point { x, y };
+
+        let line = Segment::Line {
+            from: p(1.0, 1.0),
+            to: p(4.0, 5.0),
+        };
+        assert_eq!(line.length(TOLERANCE), 5.0);
+
+        // Evenly spaced control points on a straight line
+        let cubic = Segment::Cubic {
+            from: p(0.0, 0.0),
+            ctrl1: p(1.0, 1.0),
+            ctrl2: p(2.0, 2.0),
+            to: p(3.0, 3.0),
+        };
+        assert!((cubic.length(TOLERANCE) - 18f64.sqrt()).abs() < 1e-12);
+
+        // Cusp, the speed drops to zero in the middle
+        let cubic = Segment::Cubic {
+            from: p(0.0, 0.0),
+            ctrl1: p(10.0, 10.0),
+            ctrl2: p(0.0, 10.0),
+            to: p(10.0, 0.0),
+        };
+        let expected = 18.284_271_247_462_87;
+        assert!((cubic.length(1e-10) - expected).abs() < 1e-8);
+    }
+
+    #[test]
+    fn split() {
+        let p = |x, y| Point { x, y };
+        let cubic = Segment::Cubic {
+            from: p(0.0, 0.0),
+            ctrl1: p(0.0, 10.0),
+            ctrl2: p(10.0, 10.0),
+            to: p(10.0, 0.0),
+        };
+
+        let (a, b) = cubic.split(0.5);
+        assert_eq!(
+            a,
+            Segment::Cubic {
+                from: p(0.0, 0.0),
+                ctrl1: p(0.0, 5.0),
+                ctrl2: p(2.5, 7.5),
+                to: p(5.0, 7.5),
+            }
+        );
+        assert_eq!(b.from(), a.to());
+        assert_eq!(b.to(), p(10.0, 0.0));
+
+        // Both halves trace the original curve
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            let (q, r) = (a.point_at(t), cubic.point_at(t / 2.0));
+            assert!((q.x - r.x).abs() < 1e-12 && (q.y - r.y).abs() < 1e-12);
+            let (q, r) = (b.point_at(t), cubic.point_at(0.5 + t / 2.0));
+            assert!((q.x - r.x).abs() < 1e-12 && (q.y - r.y).abs() < 1e-12);
+        }
+
+        let sub = cubic.subsegment(0.25, 0.75);
+        let (q, r) = (sub.point_at(0.5), cubic.point_at(0.5));
+        assert!((q.x - r.x).abs() < 1e-12 && (q.y - r.y).abs() < 1e-12);
+        let (q, r) = (sub.from(), cubic.point_at(0.25));
+        assert!((q.x - r.x).abs() < 1e-12 && (q.y - r.y).abs() < 1e-12);
+        assert_eq!(cubic.subsegment(0.0, 1.0), cubic);
+
+        let line = Segment::Line {
+            from: p(0.0, 0.0),
+            to: p(4.0, 8.0),
+        };
+        assert_eq!(
+            line.subsegment(0.25, 0.5),
+            Segment::Line {
+                from: p(1.0, 2.0),
+                to: p(2.0, 4.0),
+            }
+        );
+    }
+}
@@ -1,41 +1,105 @@
 use std::f64::consts::PI;
+use std::iter::FusedIterator;
 
 use crate::Command;
-use crate::parser::Point;
+use crate::parser::{ParserError, PathParser, Point};
 
 pub(crate) fn simplify(commands: &[Command]) -> Vec<Command> {
     let mut simplified = Vec::with_capacity(commands.len());
-    let mut cursor = Point { x: 0.0, y: 0.0 };
-    let mut last_control_point: Option<Point> = None;
-
+    let mut s = Simplifier::new();
     for cmd in commands {
+        s.command(cmd, &mut simplified);
+    }
+    simplified
+}
+
+/// Iterator over the simple commands of a [`PathParser`], created by
+/// [`PathParser::simplify`].
+pub struct Simplified<'a> {
+    parser: PathParser<'a>,
+    simplifier: Simplifier,
+    // Commands produced by the last parsed one, arcs give several
+    buffer: Vec<Command>,
+    index: usize,
+}
+
+impl<'a> Simplified<'a> {
+    pub(crate) fn new(parser: PathParser<'a>) -> Self {
+        Self {
+            parser,
+            simplifier: Simplifier::new(),
+            buffer: Vec::new(),
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for Simplified<'_> {
+    type Item = Result<Command, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index == self.buffer.len() {
+            self.buffer.clear();
+            self.index = 0;
+            match self.parser.next()? {
+                Ok(cmd) => self.simplifier.command(&cmd, &mut self.buffer),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.index += 1;
+        Some(Ok(self.buffer[self.index - 1].clone()))
+    }
+}
+
+impl FusedIterator for Simplified<'_> {}
+
+/// Converts commands one at a time, keeping the state needed by smooth
+/// curves and arcs.
+pub(crate) struct Simplifier {
+    cursor: Point,
+    start: Point,
+    last_control_point: Option<Point>,
+}
+
+impl Simplifier {
+    pub(crate) fn new() -> Self {
+        Self {
+            cursor: Point { x: 0.0, y: 0.0 },
+            start: Point { x: 0.0, y: 0.0 },
+            last_control_point: None,
+        }
+    }
+
+    /// Append the simple commands for `cmd` to `out`.
+    pub(crate) fn command(&mut self, cmd: &Command, out: &mut Vec<Command>) {
         match *cmd {
             Command::Move { x, y } => {
-                cursor = Point { x, y };
-                last_control_point = None;
-                simplified.push(Command::Move { x, y });
+                self.cursor = Point { x, y };
+                self.start = self.cursor;
+                self.last_control_point = None;
+                out.push(Command::Move { x, y });
             }
             Command::Line { x, y } => {
-                cursor = Point { x, y };
-                last_control_point = None;
-                simplified.push(Command::Line { x, y });
+                self.cursor = Point { x, y };
+                self.last_control_point = None;
+                out.push(Command::Line { x, y });
             }
             // Convert Horizontal to Line
             Command::Horizontal { x } => {
-                cursor.x = x;
-                last_control_point = None;
-                simplified.push(Command::Line {
-                    x: cursor.x,
-                    y: cursor.y,
+                self.cursor.x = x;
+                self.last_control_point = None;
+                out.push(Command::Line {
+                    x: self.cursor.x,
+                    y: self.cursor.y,
                 });
             }
             // Convert Vertical to Line
             Command::Vertical { y } => {
-                cursor.y = y;
-                last_control_point = None;
-                simplified.push(Command::Line {
-                    x: cursor.x,
-                    y: cursor.y,
+                self.cursor.y = y;
+                self.last_control_point = None;
+                out.push(Command::Line {
+                    x: self.cursor.x,
+                    y: self.cursor.y,
                 });
             }
             Command::Cubic {
@@ -46,9 +110,9 @@ pub(crate) fn simplify(commands: &[Command]) -> Vec<Command> {
                 x,
                 y,
             } => {
-                cursor = Point { x, y };
-                last_control_point = Some(Point { x: x2, y: y2 });
-                simplified.push(Command::Cubic {
+                self.cursor = Point { x, y };
+                self.last_control_point = Some(Point { x: x2, y: y2 });
+                out.push(Command::Cubic {
                     x1,
                     y1,
                     x2,
@@ -59,10 +123,10 @@ pub(crate) fn simplify(commands: &[Command]) -> Vec<Command> {
             }
             // Convert Smooth Cubic to Cubic
             Command::SmoothCubic { x2, y2, x, y } => {
-                let p1 = reflect(last_control_point, cursor);
-                last_control_point = Some(Point { x: x2, y: y2 });
-                cursor = Point { x, y };
-                simplified.push(Command::Cubic {
+                let p1 = reflect(self.last_control_point, self.cursor);
+                self.last_control_point = Some(Point { x: x2, y: y2 });
+                self.cursor = Point { x, y };
+                out.push(Command::Cubic {
                     x1: p1.x,
                     y1: p1.y,
                     x2,
@@ -77,16 +141,16 @@ pub(crate) fn simplify(commands: &[Command]) -> Vec<Command> {
                 let q1 = Point { x: x1, y: y1 };
                 let q2 = Point { x, y };
                 let cp1 = Point {
-                    x: cursor.x + 2.0 / 3.0 * (q1.x - cursor.x),
-                    y: cursor.y + 2.0 / 3.0 * (q1.y - cursor.y),
+                    x: self.cursor.x + 2.0 / 3.0 * (q1.x - self.cursor.x),
+                    y: self.cursor.y + 2.0 / 3.0 * (q1.y - self.cursor.y),
                 };
                 let cp2 = Point {
                     x: q2.x + 2.0 / 3.0 * (q1.x - q2.x),
                     y: q2.y + 2.0 / 3.0 * (q1.y - q2.y),
                 };
-                cursor = q2;
-                last_control_point = Some(q1);
-                simplified.push(Command::Cubic {
+                self.cursor = q2;
+                self.last_control_point = Some(q1);
+                out.push(Command::Cubic {
                     x1: cp1.x,
                     y1: cp1.y,
                     x2: cp2.x,
@@ -97,19 +161,19 @@ pub(crate) fn simplify(commands: &[Command]) -> Vec<Command> {
             }
             // Convert Smooth Quadratic to Cubic
             Command::SmoothQuadratic { x, y } => {
-                let q1 = reflect(last_control_point, cursor);
+                let q1 = reflect(self.last_control_point, self.cursor);
                 let q2 = Point { x, y };
                 let cp1 = Point {
-                    x: cursor.x + 2.0 / 3.0 * (q1.x - cursor.x),
-                    y: cursor.y + 2.0 / 3.0 * (q1.y - cursor.y),
+                    x: self.cursor.x + 2.0 / 3.0 * (q1.x - self.cursor.x),
+                    y: self.cursor.y + 2.0 / 3.0 * (q1.y - self.cursor.y),
                 };
                 let cp2 = Point {
                     x: q2.x + 2.0 / 3.0 * (q1.x - q2.x),
                     y: q2.y + 2.0 / 3.0 * (q1.y - q2.y),
                 };
-                cursor = q2;
-                last_control_point = Some(q1);
-                simplified.push(Command::Cubic {
+                self.cursor = q2;
+                self.last_control_point = Some(q1);
+                out.push(Command::Cubic {
                     x1: cp1.x,
                     y1: cp1.y,
                     x2: cp2.x,
@@ -131,7 +195,7 @@ pub(crate) fn simplify(commands: &[Command]) -> Vec<Command> {
 
                 // Convert Arc to a series of Cubic Beziers
                 let beziers = arc_to_cubics(
-                    cursor,
+                    self.cursor,
                     rx,
                     ry,
                     x_axis_rotation,
@@ -142,19 +206,19 @@ pub(crate) fn simplify(commands: &[Command]) -> Vec<Command> {
 
                 for b in beziers {
                     if let Command::Cubic { x2, y2, x, y, .. } = b {
-                        last_control_point = Some(Point { x: x2, y: y2 });
-                        cursor = Point { x, y };
+                        self.last_control_point = Some(Point { x: x2, y: y2 });
+                        self.cursor = Point { x, y };
                     }
-                    simplified.push(b);
+                    out.push(b);
                 }
             }
             Command::Close => {
-                last_control_point = None;
-                simplified.push(Command::Close);
+                self.cursor = self.start;
+                self.last_control_point = None;
+                out.push(Command::Close);
             }
         }
     }
-    simplified
 }
 
 fn reflect(last_cp: Option<Point>, cursor: Point) -> Point {
@@ -168,6 +232,67 @@ fn reflect(last_cp: Option<Point>, cursor: Point) -> Point {
 }
 
 fn arc_to_cubics(
+    start: Point,
+    rx: f64,
+    ry: f64,
+    x_axis_rot: f64,
+    large_arc: bool,
+    sweep: bool,
+    end: Point,
+) -> Vec<Command> {
+    if rx.abs() < 1e-6 || ry.abs() < 1e-6 {
+        return vec![Command::Line { x: end.x, y: end.y }];
+    }
+    let Some(arc) = arc_center(start, rx, ry, x_axis_rot, large_arc, sweep, end) else {
+        return Vec::new();
+    };
+
+    // Precise Splitting
+    let segments = (arc.delta.abs() / (PI / 2.0 + 0.001)).ceil() as u32;
+    let delta = arc.delta / segments as f64;
+    let mut result = Vec::new();
+
+    for i in 0..segments {
+        let t_start = arc.theta + i as f64 * delta;
+        result.push(single_arc_segment(
+            arc.cx, arc.cy, arc.rx, arc.ry, arc.phi, t_start, delta,
+        ));
+    }
+
+    result
+}
+
+/// Center parameterization of an SVG arc.
+pub(crate) struct ArcCenter {
+    pub(crate) cx: f64,
+    pub(crate) cy: f64,
+    /// Radii, scaled up when too small to reach the end point
+    pub(crate) rx: f64,
+    pub(crate) ry: f64,
+    /// Rotation of the x axis in radians
+    pub(crate) phi: f64,
+    /// Start angle
+    pub(crate) theta: f64,
+    /// Swept angle, negative when going counterclockwise
+    pub(crate) delta: f64,
+}
+
+impl ArcCenter {
+    pub(crate) fn point_at(&self, theta: f64) -> Point {
+        let (sin_phi, cos_phi) = self.phi.sin_cos();
+        let (x, y) = (self.rx * theta.cos(), self.ry * theta.sin());
+        Point {
+            x: cos_phi * x - sin_phi * y + self.cx,
+            y: sin_phi * x + cos_phi * y + self.cy,
+        }
+    }
+}
+
+/// Convert an arc to center parameterization (SVG implementation notes B.2.4).
+///
+/// Returns `None` for arcs that are not drawn, with a zero radius or
+/// ending where they start.
+pub(crate) fn arc_center(
     start: Point,
     mut rx: f64,
     mut ry: f64,
@@ -175,11 +300,11 @@ fn arc_to_cubics(
     large_arc: bool,
     sweep: bool,
     end: Point,
-) -> Vec<Command> {
+) -> Option<ArcCenter> {
     rx = rx.abs();
     ry = ry.abs();
-    if rx < 1e-6 || ry < 1e-6 {
-        return vec![Command::Line { x: end.x, y: end.y }];
+    if rx < 1e-6 || ry < 1e-6 || (start.x == end.x && start.y == end.y) {
+        return None;
     }
 
     // Center Parameterization (Simplified for precision)
@@ -213,35 +338,33 @@ fn arc_to_cubics(
     let cy = sin_phi * cxp + cos_phi * cyp + (start.y + end.y) / 2.0;
 
     // Angle Calculations
-    let theta1 = angle_between(1.0, 0.0, (x1p - cxp) / rx, (y1p - cyp) / ry);
-    let mut d_theta = angle_between(
+    let theta = angle_between(1.0, 0.0, (x1p - cxp) / rx, (y1p - cyp) / ry);
+    let mut delta = angle_between(
         (x1p - cxp) / rx,
         (y1p - cyp) / ry,
         (-x1p - cxp) / rx,
         (-y1p - cyp) / ry,
     );
 
-    if !sweep && d_theta > 0.0 {
-        d_theta -= 2.0 * PI;
+    if !sweep && delta > 0.0 {
+        delta -= 2.0 * PI;
     }
-    if sweep && d_theta < 0.0 {
-        d_theta += 2.0 * PI;
+    if sweep && delta < 0.0 {
+        delta += 2.0 * PI;
     }
 
-    // Precise Splitting
-    let segments = (d_theta.abs() / (PI / 2.0 + 0.001)).ceil() as u32;
-    let delta = d_theta / segments as f64;
-    let mut result = Vec::new();
-
-    for i in 0..segments {
-        let t_start = theta1 + i as f64 * delta;
-        result.push(single_arc_segment(cx, cy, rx, ry, phi, t_start, delta));
-    }
-
-    result
+    Some(ArcCenter {
+        cx,
+        cy,
+        rx,
+        ry,
+        phi,
+        theta,
+        delta,
+    })
 }
 
-fn single_arc_segment(
+pub(crate) fn single_arc_segment(
     cx: f64,
     cy: f64,
     rx: f64,
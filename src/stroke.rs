@@ -0,0 +1,377 @@
+use std::f64::consts::PI;
+
+use crate::hit::nearest_t;
+use crate::segment::{self, Segment};
+use crate::simplify::single_arc_segment;
+use crate::{Command, Point};
+
+/// Shape at the ends of open subpaths, like SVG `stroke-linecap`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+/// Shape at the corners between segments, like SVG `stroke-linejoin`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Stroke properties, defaults match SVG.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Longest miter allowed, relative to `width`, before falling back to a bevel
+    pub miter_limit: f64,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+        }
+    }
+}
+
+fn add(a: Point, b: Point, s: f64) -> Point {
+    Point {
+        x: a.x + b.x * s,
+        y: a.y + b.y * s,
+    }
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+fn normal(d: Point) -> Point {
+    Point { x: -d.y, y: d.x }
+}
+
+fn reversed(seg: &Segment) -> Segment {
+    match *seg {
+        Segment::Line { from, to } => Segment::Line { from: to, to: from },
+        Segment::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => Segment::Cubic {
+            from: to,
+            ctrl1: ctrl2,
+            ctrl2: ctrl1,
+            to: from,
+        },
+    }
+}
+
+/// One subpath to be stroked.
+struct Contour {
+    segments: Vec<Segment>,
+    closed: bool,
+    // Position for subpaths without length
+    start: Point,
+}
+
+/// Group the segments of `commands` by subpath, dropping empty segments.
+fn contours(commands: &[Command]) -> Vec<Contour> {
+    let mut result: Vec<Contour> = Vec::new();
+    let mut prev = 0;
+    let mut open = false;
+
+    for (i, seg) in segment::segments(commands) {
+        let moved = commands[prev..i]
+            .iter()
+            .any(|cmd| matches!(cmd, Command::Move { .. }));
+        if moved || !open {
+            result.push(Contour {
+                segments: Vec::new(),
+                closed: false,
+                start: seg.from(),
+            });
+        }
+        prev = i + 1;
+        let contour = result.last_mut().unwrap();
+        // A curve may have no tangent at a cusp, only its length tells it is empty
+        if seg.length(segment::TOLERANCE) > segment::TOLERANCE {
+            contour.segments.push(seg);
+        }
+        // Drawing after a closepath starts a new subpath at the same point
+        open = commands[i] != Command::Close;
+        contour.closed = !open;
+    }
+    result
+}
+
+/// Approximate the curve offset by `h` along the normal of `seg`.
+fn offset_segment(seg: &Segment, h: f64, tolerance: f64, depth: u32, out: &mut Vec<Segment>) {
+    let (Some(t0), Some(t1)) = (seg.tangent_at(0.0), seg.tangent_at(1.0)) else {
+        return;
+    };
+    let p0 = add(seg.from(), normal(t0), h);
+    let p3 = add(seg.to(), normal(t1), h);
+    let Segment::Cubic {
+        from,
+        ctrl1,
+        ctrl2,
+        to,
+    } = *seg
+    else {
+        out.push(Segment::Line { from: p0, to: p3 });
+        return;
+    };
+
+    // Keep the end tangents and match the offset point at t = 0.5:
+    // 3a t0 - 3b t1 = 8m - 4p0 - 4p3
+    let m = match seg.normal_at(0.5) {
+        Some(n) => add(seg.point_at(0.5), n, h),
+        None => seg.point_at(0.5),
+    };
+    let r = Point {
+        x: 8.0 * m.x - 4.0 * p0.x - 4.0 * p3.x,
+        y: 8.0 * m.y - 4.0 * p0.y - 4.0 * p3.y,
+    };
+    let det = -9.0 * cross(t0, t1);
+    let (a, b) = if det.abs() > 1e-9 {
+        (
+            cross(
+                r,
+                Point {
+                    x: -3.0 * t1.x,
+                    y: -3.0 * t1.y,
+                },
+            ) / det,
+            cross(
+                Point {
+                    x: 3.0 * t0.x,
+                    y: 3.0 * t0.y,
+                },
+                r,
+            ) / det,
+        )
+    } else {
+        // Parallel end tangents, scale the original handles
+        let hypot = |p: Point, q: Point| (p.x - q.x).hypot(p.y - q.y);
+        let scale = hypot(p0, p3) / hypot(from, to).max(1e-12);
+        (hypot(ctrl1, from) * scale, hypot(to, ctrl2) * scale)
+    };
+    let approx = Segment::Cubic {
+        from: p0,
+        ctrl1: add(p0, t0, a),
+        ctrl2: add(p3, t1, -b),
+        to: p3,
+    };
+
+    let error = [0.25, 0.75]
+        .into_iter()
+        .map(|t| {
+            let q = approx.point_at(t);
+            let p = seg.point_at(nearest_t(seg, q.x, q.y));
+            ((q.x - p.x).hypot(q.y - p.y) - h.abs()).abs()
+        })
+        .fold(0.0, f64::max);
+    if depth > 0 && (error > tolerance || a < 0.0 || b < 0.0) {
+        let (s1, s2) = seg.split(0.5);
+        offset_segment(&s1, h, tolerance, depth - 1, out);
+        offset_segment(&s2, h, tolerance, depth - 1, out);
+    } else {
+        out.push(approx);
+    }
+}
+
+/// Builds the outline as a list of commands.
+struct Outline<'a> {
+    style: &'a StrokeStyle,
+    h: f64,
+    tolerance: f64,
+    commands: Vec<Command>,
+}
+
+impl Outline<'_> {
+    fn move_to(&mut self, p: Point) {
+        self.commands.push(Command::Move { x: p.x, y: p.y });
+    }
+
+    fn line_to(&mut self, p: Point) {
+        self.commands.push(Command::Line { x: p.x, y: p.y });
+    }
+
+    /// Circular arc around `center` starting at `angle`, in radians.
+    fn arc(&mut self, center: Point, angle: f64, sweep: f64) {
+        let n = (sweep.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+        let delta = sweep / n as f64;
+        for i in 0..n {
+            let theta = angle + delta * i as f64;
+            let cmd = single_arc_segment(center.x, center.y, self.h, self.h, 0.0, theta, delta);
+            self.commands.push(cmd);
+        }
+    }
+
+    /// Offset segments on the normal side, joined at corners.
+    fn side(&mut self, segments: &[Segment], closed: bool) {
+        let mut pieces = Vec::new();
+        for (i, seg) in segments.iter().enumerate() {
+            if i > 0 {
+                self.join(&segments[i - 1], seg);
+            }
+            pieces.clear();
+            offset_segment(seg, self.h, self.tolerance, 8, &mut pieces);
+            for piece in &pieces {
+                self.commands.push(piece.to_command());
+            }
+        }
+        if closed && let (Some(last), Some(first)) = (segments.last(), segments.first()) {
+            self.join(last, first);
+        }
+    }
+
+    fn join(&mut self, a: &Segment, b: &Segment) {
+        let (Some(d0), Some(d1)) = (a.tangent_at(1.0), b.tangent_at(0.0)) else {
+            return;
+        };
+        let p = a.to();
+        let (n0, n1) = (normal(d0), normal(d1));
+        let end = add(p, n1, self.h);
+        let turn = cross(d0, d1);
+        let cos = dot(d0, d1);
+
+        if turn.abs() < 1e-9 && cos > 0.0 {
+            self.line_to(end);
+            return;
+        }
+        if turn > 0.0 {
+            // Inner side of the corner, the detour through the vertex fills
+            // correctly with the nonzero rule
+            self.line_to(p);
+            self.line_to(end);
+            return;
+        }
+        match self.style.join {
+            LineJoin::Miter => {
+                // Miter length relative to the stroke width is 1 / sin(angle / 2)
+                let ratio = 1.0 / ((1.0 + cos) / 2.0).sqrt();
+                if cos > -1.0 && ratio <= self.style.miter_limit {
+                    let n = add(n0, n1, 1.0);
+                    self.line_to(add(p, n, self.h / (1.0 + cos)));
+                }
+                self.line_to(end);
+            }
+            LineJoin::Round => {
+                self.arc(p, n0.y.atan2(n0.x), turn.atan2(cos));
+            }
+            LineJoin::Bevel => self.line_to(end),
+        }
+    }
+
+    /// Cap at the end of `seg`, from its left to its right offset point.
+    fn cap(&mut self, p: Point, d: Point) {
+        let n = normal(d);
+        let right = add(p, n, -self.h);
+        match self.style.cap {
+            LineCap::Butt => self.line_to(right),
+            LineCap::Square => {
+                self.line_to(add(add(p, n, self.h), d, self.h));
+                self.line_to(add(right, d, self.h));
+                self.line_to(right);
+            }
+            LineCap::Round => self.arc(p, n.y.atan2(n.x), -PI),
+        }
+    }
+
+    fn contour(&mut self, c: &Contour) {
+        let segments = &c.segments;
+        let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+            self.dot(c.start);
+            return;
+        };
+        let back: Vec<_> = segments.iter().rev().map(reversed).collect();
+
+        let start_dir = first.tangent_at(0.0).unwrap_or(Point { x: 1.0, y: 0.0 });
+        self.move_to(add(first.from(), normal(start_dir), self.h));
+        self.side(segments, c.closed);
+        if c.closed {
+            self.commands.push(Command::Close);
+            let d = back[0].tangent_at(0.0).unwrap_or(Point { x: 1.0, y: 0.0 });
+            self.move_to(add(back[0].from(), normal(d), self.h));
+            self.side(&back, true);
+            self.commands.push(Command::Close);
+            return;
+        }
+
+        let end_dir = last.tangent_at(1.0).unwrap_or(Point { x: 1.0, y: 0.0 });
+        self.cap(last.to(), end_dir);
+        self.side(&back, false);
+        self.cap(
+            first.from(),
+            Point {
+                x: -start_dir.x,
+                y: -start_dir.y,
+            },
+        );
+        self.commands.push(Command::Close);
+    }
+
+    /// Caps of a subpath without length, aligned with the x axis.
+    fn dot(&mut self, p: Point) {
+        let h = self.h;
+        match self.style.cap {
+            LineCap::Butt => {}
+            LineCap::Round => {
+                self.move_to(Point { x: p.x + h, y: p.y });
+                self.arc(p, 0.0, 2.0 * PI);
+                self.commands.push(Command::Close);
+            }
+            LineCap::Square => {
+                self.move_to(Point {
+                    x: p.x - h,
+                    y: p.y - h,
+                });
+                self.line_to(Point {
+                    x: p.x + h,
+                    y: p.y - h,
+                });
+                self.line_to(Point {
+                    x: p.x + h,
+                    y: p.y + h,
+                });
+                self.line_to(Point {
+                    x: p.x - h,
+                    y: p.y + h,
+                });
+                self.commands.push(Command::Close);
+            }
+        }
+    }
+}
+
+pub(crate) fn stroke(commands: &[Command], style: &StrokeStyle) -> Vec<Command> {
+    let h = style.width / 2.0;
+    if !(h.is_finite() && h > 0.0) {
+        return Vec::new();
+    }
+    let mut outline = Outline {
+        style,
+        h,
+        tolerance: h * 1e-3,
+        commands: Vec::new(),
+    };
+    for c in contours(commands) {
+        outline.contour(&c);
+    }
+    outline.commands
+}
@@ -0,0 +1,722 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::Matrix;
+use crate::lexer::{Position, number_len, parse_number, starts_number};
+use crate::writer::{WriteOptions, Writer};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformError {
+    UnexpectedCharacter {
+        ch: char,
+        pos: Position,
+    },
+    UnexpectedEnd {
+        pos: Position,
+    },
+    UnknownFunction {
+        name: String,
+        pos: Position,
+    },
+    InvalidNumber {
+        text: String,
+        pos: Position,
+    },
+    /// A CSS unit that does not fit the argument, or a missing one
+    InvalidUnit {
+        unit: String,
+        pos: Position,
+    },
+    ArgumentCount {
+        name: String,
+        found: usize,
+        pos: Position,
+    },
+}
+
+impl TransformError {
+    /// Where in the input this error occurred.
+    pub fn position(&self) -> Position {
+        match self {
+            TransformError::UnexpectedCharacter { pos, .. }
+            | TransformError::UnexpectedEnd { pos }
+            | TransformError::UnknownFunction { pos, .. }
+            | TransformError::InvalidNumber { pos, .. }
+            | TransformError::InvalidUnit { pos, .. }
+            | TransformError::ArgumentCount { pos, .. } => *pos,
+        }
+    }
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::UnexpectedCharacter { ch, pos } => {
+                write!(f, "{pos}: unexpected character '{ch}'")
+            }
+            TransformError::UnexpectedEnd { pos } => {
+                write!(f, "{pos}: unexpected end of input")
+            }
+            TransformError::UnknownFunction { name, pos } => {
+                write!(f, "{pos}: unknown transform function '{name}'")
+            }
+            TransformError::InvalidNumber { text, pos } => {
+                write!(f, "{pos}: invalid number '{text}'")
+            }
+            TransformError::InvalidUnit { unit, pos } if unit.is_empty() => {
+                write!(f, "{pos}: missing unit")
+            }
+            TransformError::InvalidUnit { unit, pos } => {
+                write!(f, "{pos}: invalid unit '{unit}'")
+            }
+            TransformError::ArgumentCount { name, found, pos } => {
+                write!(
+                    f,
+                    "{pos}: wrong number of arguments to '{name}', found {found}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for TransformError {}
+
+/// A single function of a transform list, angles are in degrees.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transform {
+    Translate {
+        tx: f64,
+        ty: f64,
+    },
+    Scale {
+        sx: f64,
+        sy: f64,
+    },
+    /// Rotation about `(cx, cy)`
+    Rotate {
+        angle: f64,
+        cx: f64,
+        cy: f64,
+    },
+    SkewX(f64),
+    SkewY(f64),
+    Matrix(Matrix),
+}
+
+impl Transform {
+    pub fn to_matrix(&self) -> Matrix {
+        self.apply(&Matrix::new())
+    }
+
+    /// Append this transform to `m`, like the builder methods of `Matrix`.
+    pub(crate) fn apply(&self, m: &Matrix) -> Matrix {
+        match *self {
+            Transform::Translate { tx, ty } => m.translate(tx, ty),
+            Transform::Scale { sx, sy } => m.scale(sx, sy),
+            Transform::Rotate { angle, cx, cy } => m.rotate_by(angle, cx, cy),
+            Transform::SkewX(angle) => m.skew_x(angle),
+            Transform::SkewY(angle) => m.skew_y(angle),
+            Transform::Matrix(ref other) => m.multiply(other),
+        }
+    }
+
+    /// Name and shortest arguments as written with `opts`, `None` when
+    /// it does nothing.
+    fn shortest(&self, opts: &WriteOptions) -> Option<(&'static str, Vec<f64>)> {
+        let zero = |n: f64| opts.format_number(n) == "0";
+        let same = |a: f64, b: f64| opts.format_number(a) == opts.format_number(b);
+
+        let (name, args) = match *self {
+            Transform::Translate { tx, ty } if zero(tx) && zero(ty) => return None,
+            Transform::Translate { tx, ty } if zero(ty) => ("translate", vec![tx]),
+            Transform::Translate { tx, ty } => ("translate", vec![tx, ty]),
+            Transform::Scale { sx, sy } if same(sx, 1.0) && same(sy, 1.0) => return None,
+            Transform::Scale { sx, sy } if same(sx, sy) => ("scale", vec![sx]),
+            Transform::Scale { sx, sy } => ("scale", vec![sx, sy]),
+            Transform::Rotate { angle, .. } if zero(angle) => return None,
+            Transform::Rotate { angle, cx, cy } if zero(cx) && zero(cy) => ("rotate", vec![angle]),
+            Transform::Rotate { angle, cx, cy } => ("rotate", vec![angle, cx, cy]),
+            Transform::SkewX(angle) if zero(angle) => return None,
+            Transform::SkewX(angle) => ("skewX", vec![angle]),
+            Transform::SkewY(angle) if zero(angle) => return None,
+            Transform::SkewY(angle) => ("skewY", vec![angle]),
+            Transform::Matrix(ref m) => {
+                // A matrix that only moves or only scales has a shorter form
+                if zero(m.b) && zero(m.c) && same(m.a, 1.0) && same(m.d, 1.0) {
+                    let t = Transform::Translate { tx: m.e, ty: m.f };
+                    return t.shortest(opts);
+                }
+                if zero(m.b) && zero(m.c) && zero(m.e) && zero(m.f) {
+                    let t = Transform::Scale { sx: m.a, sy: m.d };
+                    return t.shortest(opts);
+                }
+                ("matrix", vec![m.a, m.b, m.c, m.d, m.e, m.f])
+            }
+        };
+        Some((name, args))
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = TransformList {
+            items: vec![self.clone()],
+        };
+        write!(f, "{list}")
+    }
+}
+
+/// An editable list of transforms, the structured form of a `transform`
+/// attribute.
+///
+/// ```
+/// use svgpath::{Transform, TransformList};
+///
+/// let mut list = TransformList::parse("translate(10, 0) rotate(30 50 50)").unwrap();
+/// if let Transform::Rotate { angle, .. } = &mut list.items_mut()[1] {
+///     *angle += 15.0;
+/// }
+/// assert_eq!(list.to_string(), "translate(10) rotate(45 50 50)");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransformList {
+    items: Vec<Transform>,
+}
+
+impl TransformList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the value of an SVG `transform` attribute.
+    pub fn parse(input: &str) -> Result<Self, TransformError> {
+        let items = parse(input, Syntax::Svg)?;
+        Ok(Self { items })
+    }
+
+    /// Parse the value of the CSS `transform` property, see
+    /// [`Matrix::parse_css`].
+    pub fn parse_css(input: &str) -> Result<Self, TransformError> {
+        let items = parse(input, Syntax::Css)?;
+        Ok(Self { items })
+    }
+
+    pub fn items(&self) -> &[Transform] {
+        &self.items
+    }
+
+    pub fn items_mut(&mut self) -> &mut Vec<Transform> {
+        &mut self.items
+    }
+
+    pub fn push(&mut self, t: Transform) {
+        self.items.push(t);
+    }
+
+    /// Combine every transform into a single matrix.
+    pub fn to_matrix(&self) -> Matrix {
+        self.items.iter().fold(Matrix::new(), |m, t| t.apply(&m))
+    }
+
+    /// Format as the shortest equivalent attribute value using `opts`.
+    ///
+    /// Transforms that do nothing are left out and arguments equal to their
+    /// defaults are dropped.
+    pub fn to_string_with(&self, opts: &WriteOptions) -> String {
+        let mut w = Writer::new(opts);
+        for (name, args) in self.items.iter().filter_map(|t| t.shortest(opts)) {
+            w.function(name, &args);
+        }
+        w.finish()
+    }
+}
+
+impl fmt::Display for TransformList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with(&WriteOptions::default()))
+    }
+}
+
+impl From<Vec<Transform>> for TransformList {
+    fn from(items: Vec<Transform>) -> Self {
+        Self { items }
+    }
+}
+
+/// Which grammar to follow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Syntax {
+    /// The `transform` attribute of SVG 1.1
+    Svg,
+    /// The CSS `transform` property
+    Css,
+}
+
+/// Units an argument may have.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Number,
+    Length,
+    Angle,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    pos: Position,
+    syntax: Syntax,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, syntax: Syntax) -> Self {
+        Self {
+            input,
+            bytes: input.as_bytes(),
+            pos: Position::START,
+            syntax,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos.offset).copied()
+    }
+
+    /// Consume `n` ASCII bytes, none of them a line break.
+    fn advance(&mut self, n: usize) {
+        self.pos.offset += n;
+        self.pos.column += n;
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.pos.offset;
+        while let Some(b) = self.peek() {
+            if !b.is_ascii_whitespace() {
+                break;
+            }
+            if b == b'\n' {
+                self.pos.line += 1;
+                self.pos.column = 1;
+                self.pos.offset += 1;
+            } else {
+                self.advance(1);
+            }
+        }
+        self.pos.offset > start
+    }
+
+    /// Skip `comma_wsp?`, returning whether there was a comma.
+    fn skip_comma_whitespace(&mut self) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.advance(1);
+            self.skip_whitespace();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skip `comma_wsp*` after whitespace, returning whether there was a
+    /// comma.
+    fn skip_commas(&mut self) -> bool {
+        let mut comma = false;
+        while self.peek() == Some(b',') {
+            self.advance(1);
+            self.skip_whitespace();
+            comma = true;
+        }
+        comma
+    }
+
+    /// Error for whatever is at the current position.
+    fn unexpected(&self) -> TransformError {
+        let pos = self.pos;
+        match self.input[pos.offset..].chars().next() {
+            Some(ch) => TransformError::UnexpectedCharacter { ch, pos },
+            None => TransformError::UnexpectedEnd { pos },
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), TransformError> {
+        if self.peek() == Some(b) {
+            self.advance(1);
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    /// Consume the longest run of bytes matching `f`.
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos.offset;
+        let len = self.bytes[start..].iter().take_while(|b| f(**b)).count();
+        self.advance(len);
+        &self.input[start..start + len]
+    }
+
+    fn number(&mut self, kind: Kind) -> Result<f64, TransformError> {
+        let start = self.pos;
+        let rest = &self.bytes[start.offset..];
+        let mut len = number_len(rest);
+        // An 'e' without digits starts a unit ("1em")
+        if self.syntax == Syntax::Css {
+            let text = &rest[..len];
+            if text.ends_with(b"e") || text.ends_with(b"E") {
+                len -= 1;
+            } else if text.ends_with(b"e+") || text.ends_with(b"e-") {
+                len -= 2;
+            }
+        }
+        self.advance(len);
+
+        let text = &self.input[start.offset..start.offset + len];
+        let n = parse_number(text).ok_or_else(|| TransformError::InvalidNumber {
+            text: text.to_string(),
+            pos: start,
+        })?;
+        if self.syntax == Syntax::Svg {
+            return Ok(n);
+        }
+
+        let unit_pos = self.pos;
+        let unit = self.take_while(|b| b.is_ascii_alphabetic() || b == b'%');
+        let scale = match (kind, unit.to_ascii_lowercase().as_str()) {
+            (Kind::Number, "") => 1.0,
+            (Kind::Length, "px") => 1.0,
+            (Kind::Angle, "deg") => 1.0,
+            (Kind::Angle, "rad") => 180.0 / std::f64::consts::PI,
+            (Kind::Angle, "grad") => 0.9,
+            (Kind::Angle, "turn") => 360.0,
+            // Zero needs no unit
+            (Kind::Length | Kind::Angle, "") if n == 0.0 => 1.0,
+            _ => {
+                return Err(TransformError::InvalidUnit {
+                    unit: unit.to_string(),
+                    pos: unit_pos,
+                });
+            }
+        };
+        Ok(n * scale)
+    }
+
+    /// Parse `"(" wsp* number (comma_wsp? number)* wsp* ")"`.
+    fn arguments(&mut self, kind: Kind, args: &mut Vec<f64>) -> Result<(), TransformError> {
+        self.skip_whitespace();
+        self.expect(b'(')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b')') {
+            self.advance(1);
+            return Ok(());
+        }
+        loop {
+            if !self.peek().is_some_and(starts_number) {
+                return Err(self.unexpected());
+            }
+            args.push(self.number(kind)?);
+
+            let comma = self.skip_comma_whitespace();
+            if !comma && self.peek() == Some(b')') {
+                self.advance(1);
+                return Ok(());
+            }
+            // CSS separates arguments with commas
+            if self.syntax == Syntax::Css && !comma {
+                return Err(self.unexpected());
+            }
+        }
+    }
+
+    fn transform(&mut self, args: &mut Vec<f64>) -> Result<Transform, TransformError> {
+        let pos = self.pos;
+        let name = self.take_while(|b| b.is_ascii_alphanumeric());
+        let (canonical, kind) = self.function(name, pos)?;
+
+        args.clear();
+        self.arguments(kind, args)?;
+        let a = args.as_slice();
+        let found = a.len();
+        let transform = match (canonical, a) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform::Matrix(Matrix { a, b, c, d, e, f }),
+            ("translate", &[tx]) => Transform::Translate { tx, ty: 0.0 },
+            ("translate", &[tx, ty]) => Transform::Translate { tx, ty },
+            ("translatex", &[tx]) => Transform::Translate { tx, ty: 0.0 },
+            ("translatey", &[ty]) => Transform::Translate { tx: 0.0, ty },
+            ("scale", &[s]) => Transform::Scale { sx: s, sy: s },
+            ("scale", &[sx, sy]) => Transform::Scale { sx, sy },
+            ("scalex", &[sx]) => Transform::Scale { sx, sy: 1.0 },
+            ("scaley", &[sy]) => Transform::Scale { sx: 1.0, sy },
+            ("rotate", &[angle]) => Transform::Rotate {
+                angle,
+                cx: 0.0,
+                cy: 0.0,
+            },
+            ("rotate", &[angle, cx, cy]) if self.syntax == Syntax::Svg => {
+                Transform::Rotate { angle, cx, cy }
+            }
+            ("skewx", &[angle]) => Transform::SkewX(angle),
+            ("skewy", &[angle]) => Transform::SkewY(angle),
+            ("skew", &[angle]) => Transform::SkewX(angle),
+            ("skew", &[ax, ay]) => Transform::Matrix(Matrix {
+                a: 1.0,
+                b: ay.to_radians().tan(),
+                c: ax.to_radians().tan(),
+                d: 1.0,
+                e: 0.0,
+                f: 0.0,
+            }),
+            _ => {
+                return Err(TransformError::ArgumentCount {
+                    name: name.to_string(),
+                    found,
+                    pos,
+                });
+            }
+        };
+        Ok(transform)
+    }
+
+    /// Look up a function name, giving its lowercase name and argument units.
+    fn function(&self, name: &str, pos: Position) -> Result<(&'static str, Kind), TransformError> {
+        let svg = [
+            ("matrix", Kind::Number),
+            ("translate", Kind::Length),
+            ("scale", Kind::Number),
+            ("rotate", Kind::Angle),
+            ("skewX", Kind::Angle),
+            ("skewY", Kind::Angle),
+        ];
+        let css = [
+            ("translateX", Kind::Length),
+            ("translateY", Kind::Length),
+            ("scaleX", Kind::Number),
+            ("scaleY", Kind::Number),
+            ("skew", Kind::Angle),
+        ];
+
+        let found = match self.syntax {
+            // SVG names are case sensitive
+            Syntax::Svg => svg.iter().find(|(n, _)| *n == name),
+            Syntax::Css => svg
+                .iter()
+                .chain(&css)
+                .find(|(n, _)| n.eq_ignore_ascii_case(name)),
+        };
+        let Some(&(n, kind)) = found else {
+            if name.is_empty() {
+                return Err(self.unexpected());
+            }
+            return Err(TransformError::UnknownFunction {
+                name: name.to_string(),
+                pos,
+            });
+        };
+        let canonical = match n {
+            "skewX" => "skewx",
+            "skewY" => "skewy",
+            "translateX" => "translatex",
+            "translateY" => "translatey",
+            "scaleX" => "scalex",
+            "scaleY" => "scaley",
+            n => n,
+        };
+        Ok((canonical, kind))
+    }
+
+    fn parse(&mut self) -> Result<Vec<Transform>, TransformError> {
+        let mut transforms = Vec::new();
+        let mut args = Vec::with_capacity(6);
+
+        self.skip_whitespace();
+        if self.syntax == Syntax::Css && self.input.trim().eq_ignore_ascii_case("none") {
+            return Ok(transforms);
+        }
+
+        while self.peek().is_some() {
+            transforms.push(self.transform(&mut args)?);
+
+            match self.syntax {
+                Syntax::Svg => {
+                    let separated = self.skip_whitespace();
+                    let comma = self.skip_commas();
+                    // `transforms ::= transform comma_wsp+ transforms`
+                    if self.peek().is_some() && !separated && !comma {
+                        return Err(self.unexpected());
+                    }
+                    if comma && self.peek().is_none() {
+                        return Err(self.unexpected());
+                    }
+                }
+                Syntax::Css => {
+                    self.skip_whitespace();
+                }
+            }
+        }
+        Ok(transforms)
+    }
+}
+
+/// Parse a transform list following `syntax`.
+fn parse(input: &str, syntax: Syntax) -> Result<Vec<Transform>, TransformError> {
+    Parser::new(input, syntax).parse()
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn svg() {
+        let test_data = [
+            ("", vec![]),
+            (" \n ", vec![]),
+            (
+                "translate(10-5)",
+                vec![Transform::Translate { tx: 10.0, ty: -5.0 }],
+            ),
+            (
+                "rotate(45,1 2) scale(.5),skewX(3)\n skewY ( 4 ),, ,scale(2)",
+                vec![
+                    Transform::Rotate {
+                        angle: 45.0,
+                        cx: 1.0,
+                        cy: 2.0,
+                    },
+                    Transform::Scale { sx: 0.5, sy: 0.5 },
+                    Transform::SkewX(3.0),
+                    Transform::SkewY(4.0),
+                    Transform::Scale { sx: 2.0, sy: 2.0 },
+                ],
+            ),
+            (
+                "matrix(1 2 3 4 5 6)",
+                vec![Transform::Matrix(Matrix {
+                    a: 1.0,
+                    b: 2.0,
+                    c: 3.0,
+                    d: 4.0,
+                    e: 5.0,
+                    f: 6.0,
+                })],
+            ),
+        ];
+        for (s, expected) in test_data {
+            assert_eq!(parse(s, Syntax::Svg).unwrap(), expected, "{s}");
+        }
+
+        let test_data = [
+            (
+                "translate(1 2 3)",
+                "line 1, col 1: wrong number of arguments to 'translate', found 3",
+            ),
+            (
+                "matrix(1 2 3 4 5)",
+                "line 1, col 1: wrong number of arguments to 'matrix', found 5",
+            ),
+            (
+                "rotate(1 2)",
+                "line 1, col 1: wrong number of arguments to 'rotate', found 2",
+            ),
+            (
+                "scale()",
+                "line 1, col 1: wrong number of arguments to 'scale', found 0",
+            ),
+            ("rotate(45deg)", "line 1, col 10: unexpected character 'd'"),
+            (
+                "skewx(5)",
+                "line 1, col 1: unknown transform function 'skewx'",
+            ),
+            (
+                "translate(1,,2)",
+                "line 1, col 13: unexpected character ','",
+            ),
+            ("translate(1,)", "line 1, col 13: unexpected character ')'"),
+            ("scale(2),", "line 1, col 10: unexpected end of input"),
+            ("scale(2),, ", "line 1, col 12: unexpected end of input"),
+            (
+                "rotate(45,1 2)scale(.5)",
+                "line 1, col 15: unexpected character 's'",
+            ),
+            ("scale 2", "line 1, col 7: unexpected character '2'"),
+            ("scale(2", "line 1, col 8: unexpected end of input"),
+            ("scale(1e)", "line 1, col 7: invalid number '1e'"),
+            ("\nscale(2) (", "line 2, col 10: unexpected character '('"),
+        ];
+        for (s, msg) in test_data {
+            let err = parse(s, Syntax::Svg).unwrap_err();
+            assert_eq!(err.to_string(), msg, "{s}");
+        }
+    }
+
+    #[test]
+    fn css() {
+        let test_data = [
+            ("none", vec![]),
+            (
+                "translateX(10px) translate(0, -5PX)",
+                vec![
+                    Transform::Translate { tx: 10.0, ty: 0.0 },
+                    Transform::Translate { tx: 0.0, ty: -5.0 },
+                ],
+            ),
+            (
+                "rotate(0.5turn)ROTATE(-100grad) skewY(0)",
+                vec![
+                    Transform::Rotate {
+                        angle: 180.0,
+                        cx: 0.0,
+                        cy: 0.0,
+                    },
+                    Transform::Rotate {
+                        angle: -90.0,
+                        cx: 0.0,
+                        cy: 0.0,
+                    },
+                    Transform::SkewY(0.0),
+                ],
+            ),
+            (
+                "scaleY(2) scale(1e1, 3)",
+                vec![
+                    Transform::Scale { sx: 1.0, sy: 2.0 },
+                    Transform::Scale { sx: 10.0, sy: 3.0 },
+                ],
+            ),
+        ];
+        for (s, expected) in test_data {
+            assert_eq!(parse(s, Syntax::Css).unwrap(), expected, "{s}");
+        }
+
+        let rad = parse("rotate(3.14159265358979rad)", Syntax::Css).unwrap();
+        let Transform::Rotate { angle, .. } = rad[0] else {
+            panic!();
+        };
+        assert!((angle - 180.0).abs() < 1e-9);
+
+        let test_data = [
+            ("rotate(45)", "line 1, col 10: missing unit"),
+            ("translate(1em)", "line 1, col 12: invalid unit 'em'"),
+            ("translate(10%)", "line 1, col 13: invalid unit '%'"),
+            ("scale(2px)", "line 1, col 8: invalid unit 'px'"),
+            (
+                "translate(1px 2px)",
+                "line 1, col 15: unexpected character '2'",
+            ),
+            (
+                "rotate(1deg, 2deg)",
+                "line 1, col 1: wrong number of arguments to 'rotate', found 2",
+            ),
+            (
+                "scale(2), scale(3)",
+                "line 1, col 9: unexpected character ','",
+            ),
+            (
+                "rotate3d(1, 1, 1, 45deg)",
+                "line 1, col 1: unknown transform function 'rotate3d'",
+            ),
+        ];
+        for (s, msg) in test_data {
+            let err = parse(s, Syntax::Css).unwrap_err();
+            assert_eq!(err.to_string(), msg, "{s}");
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use crate::{Command, Point};
+
+/// How coordinates are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coordinates {
+    /// Uppercase commands, e.g. `M 10 20 L 30 40`
+    Absolute,
+    /// Lowercase commands relative to the current point, e.g. `m 10 20 l 20 20`
+    Relative,
+    /// Whichever of absolute or relative is shorter, chosen per command
+    Shortest,
+}
+
+/// Separator between the numbers of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// `M 10 20 L 30 40`
+    Space,
+    /// Coordinate pairs joined by a comma, `M 10,20 L 30,40`
+    Comma,
+    /// No separator unless needed and no leading zeros, `M10-5.5.5`
+    Compact,
+}
+
+/// Options to control how paths, commands and matrices are written.
+///
+/// The default options produce the same output as `Display`.
+///
+/// ```
+/// use svgpath::{Coordinates, WriteOptions};
+///
+/// let p = svgpath::parse("M 0.012,0 L 95.1205 7.09420001").unwrap();
+/// let opts = WriteOptions {
+///     precision: Some(3),
+///     coordinates: Coordinates::Relative,
+///     ..Default::default()
+/// };
+/// assert_eq!(p.to_string_with(&opts), "m 0.012 0 l 95.109 7.094");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteOptions {
+    /// Maximum number of decimal places, `None` writes every number in full precision
+    pub precision: Option<usize>,
+    /// Minimum number of decimal places, padded with trailing zeros
+    pub min_decimals: usize,
+    pub separator: Separator,
+    pub coordinates: Coordinates,
+    /// Write `H`, `V`, `S` and `T` in place of `L`, `C` and `Q` when the
+    /// result is the same within `precision`
+    pub shorthands: bool,
+    /// Leave out command letters that repeat the previous command
+    pub implicit_commands: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            precision: Some(2),
+            min_decimals: 0,
+            separator: Separator::Space,
+            coordinates: Coordinates::Absolute,
+            shorthands: false,
+            implicit_commands: false,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Produce the shortest output, like svgo does.
+    ///
+    /// Parsing the result gives back every point within half a unit of the
+    /// last written decimal place.
+    ///
+    /// ```
+    /// use svgpath::WriteOptions;
+    ///
+    /// let p = svgpath::parse("M 10 -5.5 L 10.5 -5 L 20 -5 L 20 10 Z").unwrap();
+    /// assert_eq!(p.to_string_with(&WriteOptions::minified(2)), "M10-5.5l.5.5H20V10Z");
+    /// ```
+    pub fn minified(precision: usize) -> Self {
+        Self {
+            precision: Some(precision),
+            min_decimals: 0,
+            separator: Separator::Compact,
+            coordinates: Coordinates::Shortest,
+            shorthands: true,
+            implicit_commands: true,
+        }
+    }
+
+    /// Format a single number.
+    pub fn format_number(&self, n: f64) -> String {
+        let mut s = match self.precision {
+            Some(p) => format!("{n:.p$}"),
+            None => format!("{n}"),
+        };
+        if s.contains('.') {
+            let trimmed = s.trim_end_matches('0').trim_end_matches('.').len();
+            s.truncate(trimmed);
+        }
+
+        let min = self.min_decimals.min(self.precision.unwrap_or(usize::MAX));
+        if min > 0 && n.is_finite() {
+            let decimals = match s.find('.') {
+                Some(i) => s.len() - i - 1,
+                None => {
+                    s.push('.');
+                    0
+                }
+            };
+            for _ in decimals..min {
+                s.push('0');
+            }
+        }
+
+        // Rounding may leave a negative zero behind
+        if s.starts_with('-') && s[1..].bytes().all(|b| b == b'0' || b == b'.') {
+            s.remove(0);
+        }
+
+        if self.separator == Separator::Compact {
+            if s.starts_with("0.") {
+                s.remove(0);
+            } else if s.starts_with("-0.") {
+                s.remove(1);
+            }
+        }
+        s
+    }
+
+    /// Largest distance between a coordinate and its written value.
+    fn tolerance(&self) -> f64 {
+        match self.precision {
+            Some(p) => 0.5 * 10f64.powi(-(p as i32)),
+            None => 0.0,
+        }
+    }
+
+    fn pair_separator(&self) -> char {
+        match self.separator {
+            Separator::Comma => ',',
+            _ => ' ',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Arg {
+    X,
+    Y,
+    Rx,
+    Ry,
+    Angle,
+    Flag,
+}
+
+fn arg_kinds(letter: char) -> &'static [Arg] {
+    use Arg::*;
+    match letter.to_ascii_uppercase() {
+        'M' | 'L' | 'T' => &[X, Y],
+        'H' => &[X],
+        'V' => &[Y],
+        'C' => &[X, Y, X, Y, X, Y],
+        'S' | 'Q' => &[X, Y, X, Y],
+        'A' => &[Rx, Ry, Angle, Flag, Flag, X, Y],
+        _ => &[],
+    }
+}
+
+/// The last number written, as far as separators are concerned.
+#[derive(Debug, Clone, Copy)]
+struct LastNumber {
+    has_dot: bool,
+    kind: Arg,
+}
+
+/// Separator needed between two numbers.
+fn separator(opts: &WriteOptions, prev: LastNumber, kind: Arg, text: &str) -> Option<char> {
+    if opts.separator != Separator::Compact {
+        let pair = matches!((prev.kind, kind), (Arg::X, Arg::Y) | (Arg::Rx, Arg::Ry));
+        return Some(if pair { opts.pair_separator() } else { ' ' });
+    }
+    // Flags are read one digit at a time
+    if prev.kind == Arg::Flag {
+        return None;
+    }
+    match text.as_bytes().first() {
+        Some(b'-' | b'+') => None,
+        Some(b'.') if prev.has_dot => None,
+        _ => Some(' '),
+    }
+}
+
+fn reflect(p: Option<Point>, center: Point) -> Point {
+    match p {
+        Some(p) => Point {
+            x: 2.0 * center.x - p.x,
+            y: 2.0 * center.y - p.y,
+        },
+        None => center,
+    }
+}
+
+/// Writes commands while tracking the state a parser reading the output would have.
+pub(crate) struct Writer<'a> {
+    opts: &'a WriteOptions,
+    out: String,
+    cursor: Point,
+    start: Point,
+    // Second control point of the last C or S, for S reflection
+    last_cubic: Option<Point>,
+    // Control point of the last Q or T, for T reflection
+    last_quad: Option<Point>,
+    last_letter: Option<char>,
+    last_number: Option<LastNumber>,
+}
+
+/// One way to write a command.
+struct Candidate {
+    text: String,
+    letter: char,
+    omitted: bool,
+    // Absolute values as they will be read back
+    values: [f64; 7],
+    last_number: Option<LastNumber>,
+}
+
+impl<'a> Writer<'a> {
+    pub(crate) fn new(opts: &'a WriteOptions) -> Self {
+        Self {
+            opts,
+            out: String::new(),
+            cursor: Point { x: 0.0, y: 0.0 },
+            start: Point { x: 0.0, y: 0.0 },
+            last_cubic: None,
+            last_quad: None,
+            last_letter: None,
+            last_number: None,
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.out
+    }
+
+    pub(crate) fn matrix(&mut self, nums: &[f64; 6]) {
+        self.function("matrix", nums);
+    }
+
+    /// Write a transform function like `rotate(30 50 50)`, after a space if
+    /// something was written before.
+    pub(crate) fn function(&mut self, name: &str, nums: &[f64]) {
+        if !self.out.is_empty() {
+            self.out.push(' ');
+        }
+        self.out.push_str(name);
+        self.out.push('(');
+        let mut prev: Option<LastNumber> = None;
+        for n in nums {
+            let text = self.opts.format_number(*n);
+            if let Some(p) = prev {
+                let sep = match self.opts.separator {
+                    Separator::Compact => separator(self.opts, p, Arg::Angle, &text),
+                    _ => Some(self.opts.pair_separator()),
+                };
+                self.out.extend(sep);
+            }
+            self.out.push_str(&text);
+            prev = Some(LastNumber {
+                has_dot: text.contains('.'),
+                kind: Arg::Angle,
+            });
+        }
+        self.out.push(')');
+    }
+
+    /// Render command `letter` (uppercase) with absolute values `abs`.
+    fn render(&self, letter: char, abs: &[f64], relative: bool) -> Candidate {
+        let compact = self.opts.separator == Separator::Compact;
+        let origin = if relative {
+            self.cursor
+        } else {
+            Point { x: 0.0, y: 0.0 }
+        };
+        let letter = if relative {
+            letter.to_ascii_lowercase()
+        } else {
+            letter
+        };
+        let kinds = arg_kinds(letter);
+
+        // A moveto followed by numbers continues as lineto
+        let omitted = self.opts.implicit_commands
+            && !kinds.is_empty()
+            && match (self.last_letter, letter) {
+                (Some('M'), 'L') | (Some('m'), 'l') => true,
+                (Some(prev), _) => prev == letter && !matches!(letter, 'M' | 'm'),
+                (None, _) => false,
+            };
+
+        let mut text = String::new();
+        let mut prev = None;
+        if omitted {
+            prev = self.last_number;
+        } else {
+            if !self.out.is_empty() && !compact {
+                text.push(' ');
+            }
+            text.push(letter);
+        }
+
+        let mut values = [0.0; 7];
+        for (i, &kind) in kinds.iter().enumerate() {
+            let (v, o) = match kind {
+                Arg::X => (abs[i] - origin.x, origin.x),
+                Arg::Y => (abs[i] - origin.y, origin.y),
+                _ => (abs[i], 0.0),
+            };
+            let num = if kind == Arg::Flag {
+                if v != 0.0 { "1" } else { "0" }.to_string()
+            } else {
+                self.opts.format_number(v)
+            };
+            values[i] = o + num.parse().unwrap_or(v);
+
+            match prev {
+                Some(p) => text.extend(separator(self.opts, p, kind, &num)),
+                None if !compact => text.push(' '),
+                None => {}
+            }
+            text.push_str(&num);
+            prev = Some(LastNumber {
+                has_dot: num.contains('.'),
+                kind,
+            });
+        }
+
+        Candidate {
+            text,
+            letter,
+            omitted,
+            values,
+            last_number: prev,
+        }
+    }
+
+    pub(crate) fn command(&mut self, cmd: &Command) {
+        let tol = self.opts.tolerance();
+        let shorthands = self.opts.shorthands;
+        let near = |a: Point, x: f64, y: f64| (a.x - x).abs() <= tol && (a.y - y).abs() <= tol;
+
+        // Equivalent forms of this command
+        let mut forms: Vec<(char, Vec<f64>)> = Vec::with_capacity(3);
+        match *cmd {
+            Command::Move { x, y } => forms.push(('M', vec![x, y])),
+            Command::Line { x, y } => {
+                forms.push(('L', vec![x, y]));
+                if shorthands && (y - self.cursor.y).abs() <= tol {
+                    forms.push(('H', vec![x]));
+                }
+                if shorthands && (x - self.cursor.x).abs() <= tol {
+                    forms.push(('V', vec![y]));
+                }
+            }
+            Command::Horizontal { x } => forms.push(('H', vec![x])),
+            Command::Vertical { y } => forms.push(('V', vec![y])),
+            Command::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                forms.push(('C', vec![x1, y1, x2, y2, x, y]));
+                if shorthands && near(reflect(self.last_cubic, self.cursor), x1, y1) {
+                    forms.push(('S', vec![x2, y2, x, y]));
+                }
+            }
+            Command::SmoothCubic { x2, y2, x, y } => forms.push(('S', vec![x2, y2, x, y])),
+            Command::Quadratic { x1, y1, x, y } => {
+                forms.push(('Q', vec![x1, y1, x, y]));
+                if shorthands && near(reflect(self.last_quad, self.cursor), x1, y1) {
+                    forms.push(('T', vec![x, y]));
+                }
+            }
+            Command::SmoothQuadratic { x, y } => forms.push(('T', vec![x, y])),
+            Command::Arc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc_flag,
+                sweep_flag,
+                x,
+                y,
+            } => {
+                let large = if large_arc_flag { 1.0 } else { 0.0 };
+                let sweep = if sweep_flag { 1.0 } else { 0.0 };
+                forms.push(('A', vec![rx, ry, x_axis_rotation, large, sweep, x, y]));
+            }
+            Command::Close => forms.push(('Z', vec![])),
+        }
+
+        let modes: &[bool] = match self.opts.coordinates {
+            Coordinates::Absolute => &[false],
+            Coordinates::Relative => &[true],
+            Coordinates::Shortest => &[false, true],
+        };
+
+        let mut best: Option<Candidate> = None;
+        for (letter, abs) in &forms {
+            for &relative in modes {
+                let c = self.render(*letter, abs, relative);
+                if best.as_ref().is_none_or(|b| c.text.len() < b.text.len()) {
+                    best = Some(c);
+                }
+            }
+        }
+        let Some(c) = best else { return };
+        self.commit(c);
+    }
+
+    fn commit(&mut self, c: Candidate) {
+        let w = c.values;
+        let (mut last_cubic, mut last_quad) = (None, None);
+        match c.letter.to_ascii_uppercase() {
+            'M' => {
+                self.cursor = Point { x: w[0], y: w[1] };
+                self.start = self.cursor;
+            }
+            'L' => self.cursor = Point { x: w[0], y: w[1] },
+            'H' => self.cursor.x = w[0],
+            'V' => self.cursor.y = w[0],
+            'C' => {
+                last_cubic = Some(Point { x: w[2], y: w[3] });
+                self.cursor = Point { x: w[4], y: w[5] };
+            }
+            'S' => {
+                last_cubic = Some(Point { x: w[0], y: w[1] });
+                self.cursor = Point { x: w[2], y: w[3] };
+            }
+            'Q' => {
+                last_quad = Some(Point { x: w[0], y: w[1] });
+                self.cursor = Point { x: w[2], y: w[3] };
+            }
+            'T' => {
+                last_quad = Some(reflect(self.last_quad, self.cursor));
+                self.cursor = Point { x: w[0], y: w[1] };
+            }
+            'A' => self.cursor = Point { x: w[5], y: w[6] },
+            _ => self.cursor = self.start,
+        }
+        self.last_cubic = last_cubic;
+        self.last_quad = last_quad;
+        if !c.omitted {
+            self.last_letter = Some(c.letter);
+        }
+        self.last_number = c.last_number;
+        self.out.push_str(&c.text);
+    }
+}
+
+pub(crate) fn write_path(commands: &[Command], opts: &WriteOptions) -> String {
+    let mut w = Writer::new(opts);
+    for cmd in commands {
+        w.command(cmd);
+    }
+    w.finish()
+}
@@ -0,0 +1,137 @@
+// Conformance with the path data grammar of SVG 1.1 (section 8.3.9) and
+// SVG 2 (section 9.3.9). Each test follows one production of the BNF.
+
+fn valid(test_data: &[(&str, &str)]) {
+    for (input, output) in test_data {
+        match svgpath::parse(input) {
+            Ok(p) => assert_eq!(p.to_string(), *output, "{input:?}"),
+            Err(e) => panic!("{input:?}: {e}"),
+        }
+    }
+}
+
+fn invalid(test_data: &[&str]) {
+    for input in test_data {
+        assert!(svgpath::parse(input).is_err(), "{input:?}");
+    }
+}
+
+// svg_path ::= wsp* moveto? (moveto drawto_command*)? wsp*
+#[test]
+fn svg_path() {
+    valid(&[
+        ("M 1 2", "M 1 2"),
+        (" \t\r\n\x0cM 1 2 \t\r\n\x0c", "M 1 2"),
+        ("M 1 2 M 3 4", "M 1 2 M 3 4"),
+        ("M1 2L3 4", "M 1 2 L 3 4"),
+        ("M1 2\nL3 4\n", "M 1 2 L 3 4"),
+    ]);
+    invalid(&["", " ", "L 1 2", "1 2", "z", "M 1 2 \x0b", "M 1 2 \u{a0}"]);
+}
+
+// wsp ::= (#x9 | #x20 | #xA | #xC | #xD)
+// comma_wsp ::= (wsp+ ","? wsp*) | ("," wsp*)
+#[test]
+fn comma_wsp() {
+    valid(&[
+        ("M 1,2", "M 1 2"),
+        ("M 1 , 2", "M 1 2"),
+        ("M 1 ,2", "M 1 2"),
+        ("M 1, 2", "M 1 2"),
+        ("M 1 2,3 4", "M 1 2 L 3 4"),
+        ("M 1-2", "M 1 -2"),
+        ("M .1.2", "M 0.1 0.2"),
+    ]);
+    invalid(&[
+        "M 1,,2",
+        "M 1, ,2",
+        ",M 1 2",
+        "M,1 2",
+        "M 1 2,",
+        "M 1 2, L 3 4",
+        "M 1 2 L,3 4",
+        "M 1 2 Z,",
+        "M 1 2 Z, M 3 4",
+        "M 1;2",
+    ]);
+}
+
+// number ::= sign? (integer_constant | floating_point_constant)
+// floating_point_constant ::= (fractional_constant exponent?) | (digit_sequence exponent)
+// fractional_constant ::= (digit_sequence? "." digit_sequence) | (digit_sequence ".")
+// exponent ::= ("e" | "E") sign? digit_sequence
+#[test]
+fn number() {
+    valid(&[
+        ("M 1 +2", "M 1 2"),
+        ("M 1. -2.", "M 1 -2"),
+        ("M .5 -.5", "M 0.5 -0.5"),
+        ("M 1e2 1E2", "M 100 100"),
+        ("M 1e+2 1e-2", "M 100 0.01"),
+        ("M 1.e2 .1e2", "M 100 10"),
+        ("M 10.5.5", "M 10.5 0.5"),
+        ("M 1.5e2.5", "M 150 0.5"),
+        ("M 00012 0.0", "M 12 0"),
+        ("M 1e-2-3", "M 0.01 -3"),
+    ]);
+    invalid(&[
+        "M . 1", "M 1 .", "M - 1 2", "M +-1 2", "M 1e 2", "M 1e+ 2", "M 1 e2", "M 1 2e",
+        "M 0x10 2", "M inf 2", "M 1 NaN",
+    ]);
+}
+
+// elliptical_arc_argument ::= number comma_wsp? number comma_wsp? number comma_wsp
+//     flag comma_wsp? flag comma_wsp? coordinate_pair
+// flag ::= ("0" | "1")
+#[test]
+fn flag() {
+    valid(&[
+        ("M 0 0 A 1 2 3 0 1 4 5", "M 0 0 A 1 2 3 0 1 4 5"),
+        ("M 0 0 A 1,2,3,1,0,4,5", "M 0 0 A 1 2 3 1 0 4 5"),
+        ("M 0 0 A 1 2 3 1 0-4-5", "M 0 0 A 1 2 3 1 0 -4 -5"),
+        ("M 0 0 A 1 2 3 01 4 5", "M 0 0 A 1 2 3 0 1 4 5"),
+        ("M 0 0 A 1 2 3 0 14 5", "M 0 0 A 1 2 3 0 1 4 5"),
+        ("M 0 0 a1 2 3 114 5", "M 0 0 A 1 2 3 1 1 4 5"),
+        ("M 0 0 a1 2 3 11.5.5", "M 0 0 A 1 2 3 1 1 0.5 0.5"),
+        ("M 0 0 A 1 2 3 0,0,4,5", "M 0 0 A 1 2 3 0 0 4 5"),
+    ]);
+    invalid(&[
+        "M 0 0 A 1 2 3 2 0 4 5",
+        "M 0 0 A 1 2 3 0 2 4 5",
+        "M 0 0 A 1 2 3 -1 0 4 5",
+        "M 0 0 A 1 2 3 +1 0 4 5",
+        "M 0 0 A 1 2 3 1.0 0 4 5",
+        "M 0 0 A 1 2 3 .1 0 4 5",
+        "M 0 0 A 1 2 3 1 1e0 4 5",
+        "M 0 0 A 1 2 3 1 0 4",
+        "M 0 0 A 1 2 3 1 L 4 5",
+        // Without a separator "01" is the rotation
+        "M 0 0 A 1 2 01 4 5",
+    ]);
+}
+
+// drawto_command ::= moveto | closepath | lineto | horizontal_lineto
+//     | vertical_lineto | curveto | smooth_curveto | quadratic_bezier_curveto
+//     | smooth_quadratic_bezier_curveto | elliptical_arc
+#[test]
+fn drawto_command() {
+    valid(&[
+        ("M 0 0 L 1 2 3 4", "M 0 0 L 1 2 L 3 4"),
+        ("M 0 0 H 1 2 V 3,4", "M 0 0 H 1 H 2 V 3 V 4"),
+        ("M 0 0 C 1 2 3 4 5 6", "M 0 0 C 1 2 3 4 5 6"),
+        (
+            "M 0 0 S 1 2 3 4 Q 5 6 7 8 T 9 10",
+            "M 0 0 S 1 2 3 4 Q 5 6 7 8 T 9 10",
+        ),
+        ("M 0 0 z m 1 2", "M 0 0 Z M 1 2"),
+        ("M 0 0 Z Z", "M 0 0 Z Z"),
+    ]);
+    invalid(&[
+        "M 0 0 L",
+        "M 0 0 L 1",
+        "M 0 0 C 1 2 3 4 5",
+        "M 0 0 Z 1 2",
+        "M 0 0 B 1 2",
+        "M 0 0 l 1 2 3",
+    ]);
+}
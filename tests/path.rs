@@ -1,4 +1,4 @@
-use svgpath::Command;
+use svgpath::{Command, Matrix, PathParser};
 
 #[test]
 fn basic() {
@@ -29,7 +29,7 @@ fn basic() {
     assert_eq!(it.next(), Some(&Command::Line { x: 100.0, y: 75.0 }));
     assert_eq!(it.next(), Some(&Command::Horizontal { x: 50.0 }));
     assert_eq!(it.next(), Some(&Command::Close));
-    assert_eq!(it.next(), None);
+    assert!(it.next().is_none());
 }
 
 #[test]
@@ -65,3 +65,354 @@ fn invalid() {
         assert!(p.is_err());
     }
 }
+
+#[test]
+fn lenient() {
+    let test_data = [
+        ("M 0 0 L 10 10 L 5", "M 0 0 L 10 10", true),
+        ("M 0 0 L 10 10", "M 0 0 L 10 10", false),
+        ("M 1 2 3 4 5", "M 1 2 L 3 4", true),
+        ("M 9,5 h 20 Z 0", "M 9 5 H 29 Z", true),
+        ("M 5 5 H 10 X 7 3 Z", "M 5 5 H 10", true),
+        ("M 0 0 a 1 1 0 0 1", "M 0 0", true),
+        ("L 15 37 v 30", "", true),
+        ("5 M 0 0", "", true),
+        ("", "", false),
+        ("  \n ", "", false),
+    ];
+    for (input, output, has_err) in test_data {
+        let (p, err) = svgpath::parse_lenient(input);
+        assert_eq!(p.to_string(), output);
+        assert_eq!(err.is_some(), has_err);
+    }
+
+    let (_, err) = svgpath::parse_lenient("M 0 0 L 10 10 L 5");
+    assert_eq!(
+        err.unwrap().to_string(),
+        "line 1, col 18: expected 2 numbers after 'L', found 1"
+    );
+}
+
+#[test]
+fn write_options() {
+    use svgpath::{Coordinates, Separator, WriteOptions};
+
+    let p = svgpath::parse("M 0.012,0 L 95.1205 7.09420001 h -5 Z").unwrap();
+    #[rustfmt::skip]
+    let test_data = [
+        (Some(2), 0, Separator::Space, Coordinates::Absolute, "M 0.01 0 L 95.12 7.09 H 90.12 Z"),
+        (Some(4), 0, Separator::Space, Coordinates::Absolute, "M 0.012 0 L 95.1205 7.0942 H 90.1205 Z"),
+        (None, 0, Separator::Space, Coordinates::Absolute, "M 0.012 0 L 95.1205 7.09420001 H 90.1205 Z"),
+        (Some(3), 3, Separator::Comma, Coordinates::Absolute, "M 0.012,0.000 L 95.121,7.094 H 90.121 Z"),
+        (Some(1), 2, Separator::Space, Coordinates::Absolute, "M 0.0 0.0 L 95.1 7.1 H 90.1 Z"),
+        (Some(2), 0, Separator::Comma, Coordinates::Relative, "m 0.01,0 l 95.11,7.09 h -5 z"),
+    ];
+    for (precision, min_decimals, separator, coordinates, expected) in test_data {
+        let opts = WriteOptions {
+            precision,
+            min_decimals,
+            separator,
+            coordinates,
+            ..Default::default()
+        };
+        assert_eq!(p.to_string_with(&opts), expected);
+    }
+
+    // Relative output does not accumulate rounding errors
+    let p = svgpath::parse("M 0.4 0 L 0.8 0 L 1.2 0 L 1.6 0 M 2 2 L 3 3 Z L 1 0").unwrap();
+    let opts = WriteOptions {
+        precision: Some(0),
+        coordinates: Coordinates::Relative,
+        ..Default::default()
+    };
+    let s = p.to_string_with(&opts);
+    assert_eq!(s, "m 0 0 l 1 0 l 0 0 l 1 0 m 0 2 l 1 1 z l -1 -2");
+    let q = svgpath::parse(&s).unwrap();
+    assert_eq!(q.to_string(), "M 0 0 L 1 0 L 1 0 L 2 0 M 2 2 L 3 3 Z L 1 0");
+
+    let m = svgpath::Matrix::new().translate(1.5, -2.0);
+    let opts = WriteOptions {
+        separator: Separator::Comma,
+        min_decimals: 1,
+        ..Default::default()
+    };
+    assert_eq!(m.to_string_with(&opts), "matrix(1.0,0.0,0.0,1.0,1.5,-2.0)");
+}
+
+#[test]
+fn minified() {
+    use svgpath::{Command, WriteOptions};
+
+    #[rustfmt::skip]
+    let test_data = [
+        ["M 10 -5.5 L 10.5 -5 L 20 -5 L 20 10 Z", "M10-5.5l.5.5H20V10Z"],
+        ["M 0 0 L 10 10 L 20 20 L 30 30", "M0 0 10 10 20 20 30 30"],
+        ["M 100 100 L 101 101 L 102 102", "M100 100l1 1 1 1"],
+        ["M 0.5 0.25 L -0.5 -0.25", "M.5.25l-1-.5"],
+        ["M 10 30 A 20 20 0 0 1 50 30 A 20 20 0 1 0 90 30", "M10 30a20 20 0 0140 0 20 20 0 1040 0"],
+        ["M 0 0 C 0 10 10 10 10 0 C 10 -10 20 -10 20 0", "M0 0C0 10 10 10 10 0S20-10 20 0"],
+        ["M 0 0 Q 5 10 10 0 Q 15 -10 20 0", "M0 0Q5 10 10 0T20 0"],
+        ["M 0 0 C 0 0 5 5 10 0", "M0 0S5 5 10 0"],
+        ["M 5 5 M 10 10 Z", "M5 5m5 5Z"],
+    ];
+    for [input, expected] in test_data {
+        let p = svgpath::parse(input).unwrap();
+        let s = p.to_string_with(&WriteOptions::minified(2));
+        assert_eq!(s, expected);
+        let q = svgpath::parse(&s).unwrap();
+        assert_eq!(q.simplify().to_string(), p.simplify().to_string());
+    }
+
+    // Re-parsing gives back the geometry within the precision
+    let input = "
+        M 12.3456 78.9012 C 13.1111 80.2222 15.9876 82.5432 17.0001 81.0009
+        C 18.0126 79.4586 19.4444 77.7777 21.2121 78.7878 L 21.2149 92.1234
+        Q 25.4321 95.6789 29.1357 93.2468 T 33.3333 90.0001 L 12.3456 90.0049 Z
+        M -4.44 -5.55 l 0.004 3.3333 l 2.2222 0.0049 z";
+    let p = svgpath::parse(input).unwrap().simplify();
+    for precision in [0, 1, 2, 3] {
+        let s = p.to_string_with(&WriteOptions::minified(precision));
+        let q = svgpath::parse(&s).unwrap().simplify();
+        let tol = 0.5 * 10f64.powi(-(precision as i32)) + 1e-9;
+        let a: Vec<_> = p.commands().collect();
+        let b: Vec<_> = q.commands().collect();
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b) {
+            match (a, b) {
+                (Command::Move { x, y }, Command::Move { x: bx, y: by })
+                | (Command::Line { x, y }, Command::Line { x: bx, y: by }) => {
+                    assert!((x - bx).abs() <= tol && (y - by).abs() <= tol);
+                }
+                (
+                    Command::Cubic {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                    },
+                    Command::Cubic {
+                        x1: bx1,
+                        y1: by1,
+                        x2: bx2,
+                        y2: by2,
+                        x: bx,
+                        y: by,
+                    },
+                ) => {
+                    for (u, v) in [(x1, bx1), (y1, by1), (x2, bx2), (y2, by2), (x, bx), (y, by)] {
+                        assert!((u - v).abs() <= tol);
+                    }
+                }
+                (Command::Close, Command::Close) => {}
+                _ => panic!("{s}: {a} != {b}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn compact_arc_flags() {
+    let p = svgpath::parse("M0 0a1 1 0 0110 10A 5,5 0 1,0 3 4").unwrap();
+    assert_eq!(p.to_string(), "M 0 0 A 1 1 0 0 1 10 10 A 5 5 0 1 0 3 4");
+}
+
+#[test]
+fn streaming() {
+    let s = "M 10 20 l 5 5 h 10 Q 30 30 40 20 t 10 0 a 5 5 0 0 1 10 0 z m 1 1 2 2";
+    let commands = PathParser::new(s).collect::<Result<Vec<_>, _>>().unwrap();
+    let p = svgpath::parse(s).unwrap();
+    assert_eq!(commands, p.commands().cloned().collect::<Vec<_>>());
+
+    let simple = PathParser::new(s)
+        .simplify()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(simple, p.simplify().commands().cloned().collect::<Vec<_>>());
+
+    // Stops after the first error
+    let mut it = PathParser::new("M 1 2 L 3 L 4 5");
+    assert_eq!(
+        it.next().unwrap().ok(),
+        Some(Command::Move { x: 1.0, y: 2.0 })
+    );
+    assert!(matches!(it.next(), Some(Err(_))));
+    assert!(it.next().is_none());
+
+    let mut it = PathParser::new("  ");
+    assert!(matches!(it.next(), Some(Err(_))));
+    assert!(it.next().is_none());
+
+    // A quadratic after close starts from the subpath start
+    let simple = PathParser::new("M 10 10 L 20 10 Z Q 10 40 40 10")
+        .simplify()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        simple[3],
+        Command::Cubic {
+            x1: 10.0,
+            y1: 30.0,
+            x2: 20.0,
+            y2: 30.0,
+            x: 40.0,
+            y: 10.0,
+        }
+    );
+
+    let m = Matrix::new().translate(10.0, 0.0);
+    let moved = PathParser::new("M 0 0 Q 5 5 10 0 H 20")
+        .transform(&m)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        moved,
+        [
+            Command::Move { x: 10.0, y: 0.0 },
+            Command::Quadratic {
+                x1: 15.0,
+                y1: 5.0,
+                x: 20.0,
+                y: 0.0,
+            },
+            Command::Line { x: 30.0, y: 0.0 },
+        ]
+    );
+
+    // Lines follow the current point, also after a close
+    let m = Matrix::new().scale(2.0, 3.0);
+    let s = "M 1 1 h 4 v 2 z v 5";
+    let streamed = PathParser::new(s)
+        .transform(&m)
+        .map(|c| c.unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(streamed, ["M 2 3", "L 10 3", "L 10 9", "Z", "L 2 18"]);
+    assert!(
+        PathParser::new("M 1 2 H")
+            .transform(&m)
+            .last()
+            .unwrap()
+            .is_err()
+    );
+}
+
+#[test]
+fn transform() {
+    let p = svgpath::parse("M 10 20 h 5 v 5 H 0 Z v 10 Q 5 5 10 0 T 20 0 S 30 10 40 0").unwrap();
+
+    let m = Matrix::new().translate(5.0, 5.0).scale(2.0, 3.0);
+    assert_eq!(
+        p.transform(&m).to_string(),
+        "M 25 65 H 35 V 80 H 5 Z V 95 Q 15 20 25 5 T 45 5 S 65 35 85 5"
+    );
+
+    // A quarter turn swaps H and V, other rotations need L
+    let s = svgpath::parse("M 1 2 H 5 V 7").unwrap();
+    let m = Matrix {
+        a: 0.0,
+        b: 1.0,
+        c: -1.0,
+        d: 0.0,
+        e: 0.0,
+        f: 0.0,
+    };
+    assert_eq!(s.transform(&m).to_string(), "M -2 1 V 5 H -7");
+    let m = Matrix::new().rotate(45.0).scale(2f64.sqrt(), 2f64.sqrt());
+    assert_eq!(s.transform(&m).to_string(), "M -1 3 L 3 7 L -2 12");
+
+    // The transformed path matches the transformed simplified path
+    let s = "M 10 10 A 20 10 30 0 1 50 30 a 5 15 -20 1 0 -10 10 H 0 Z";
+    let p = svgpath::parse(s).unwrap();
+    for m in [
+        Matrix::new().rotate(30.0).scale(2.0, 0.5),
+        Matrix::new().scale(-1.0, 1.0),
+        Matrix::new().skew_x(25.0).translate(7.0, -3.0),
+        Matrix::new().scale(3.0, 3.0).rotate(-60.0),
+    ] {
+        let a = p.transform(&m).simplify();
+        let b = p.simplify().transform(&m);
+        let pa = a.polylines(0.01).flat_map(|l| l.points).collect::<Vec<_>>();
+        for q in b.polylines(0.01).flat_map(|l| l.points) {
+            let d = pa
+                .iter()
+                .map(|p| (p.x - q.x).hypot(p.y - q.y))
+                .fold(f64::INFINITY, f64::min);
+            assert!(d < 0.1, "{m}: {d}");
+        }
+        assert!(
+            p.transform(&m)
+                .commands()
+                .any(|c| matches!(c, Command::Arc { .. }))
+        );
+    }
+
+    // Rotation and radii are kept where possible
+    let p = svgpath::parse("M 0 0 A 20 10 30 0 1 50 30").unwrap();
+    assert_eq!(
+        p.transform(&Matrix::new().scale(-2.0, 2.0)).to_string(),
+        "M 0 0 A 40 20 -30 0 0 -100 60"
+    );
+    let m = Matrix::new().rotate(15.0);
+    let Some(Command::Arc {
+        rx,
+        ry,
+        x_axis_rotation,
+        ..
+    }) = p.transform(&m).commands().nth(1).cloned()
+    else {
+        panic!();
+    };
+    assert!((rx - 20.0).abs() < 1e-9 && (ry - 10.0).abs() < 1e-9);
+    assert!((x_axis_rotation - 45.0).abs() < 1e-9);
+}
+
+#[test]
+fn bbox() {
+    let bb = |s: &str| {
+        let b = svgpath::parse(s).unwrap().bbox().unwrap();
+        [b.min_x, b.min_y, b.max_x, b.max_y]
+    };
+    let loose = |s: &str| {
+        let b = svgpath::parse(s).unwrap().loose_bbox().unwrap();
+        [b.min_x, b.min_y, b.max_x, b.max_y]
+    };
+
+    assert_eq!(bb("M 5 5 h 10 v -20 H 0"), [0.0, -15.0, 15.0, 5.0]);
+    assert_eq!(bb("M 0 0 Q 10 20 20 0"), [0.0, 0.0, 20.0, 10.0]);
+    assert_eq!(loose("M 0 0 Q 10 20 20 0"), [0.0, 0.0, 20.0, 20.0]);
+    assert_eq!(bb("M 0 0 Q 10 20 20 0 T 40 0"), [0.0, -10.0, 40.0, 10.0]);
+    assert_eq!(
+        bb("M 0 0 C 0 10 20 10 20 0 S 40 -10 40 0"),
+        [0.0, -7.5, 40.0, 7.5]
+    );
+    assert_eq!(bb("M 0 0 A 10 10 0 0 1 20 0"), [0.0, -10.0, 20.0, 0.0]);
+    assert_eq!(bb("M 0 0 A 10 10 0 0 0 20 0"), [0.0, 0.0, 20.0, 10.0]);
+    assert_eq!(loose("M 0 0 A 10 10 0 0 0 20 0"), [0.0, -10.0, 20.0, 10.0]);
+    // Radii too small are scaled up, zero radii draw a line
+    assert_eq!(bb("M 0 0 A 1 1 0 0 1 20 0"), [0.0, -10.0, 20.0, 0.0]);
+    assert_eq!(bb("M 0 0 A 0 5 0 0 1 20 5"), [0.0, 0.0, 20.0, 5.0]);
+
+    // Compare with the flattened path for a rotated ellipse
+    let s = "M 10 10 A 30 10 40 1 0 50 30 Z m 5 5 a 8 3 -70 0 1 -10 10";
+    let p = svgpath::parse(s).unwrap();
+    let exact = p.bbox().unwrap();
+    let flat = p.simplify().flatten(1e-4).bbox().unwrap();
+    let cubic = p.simplify().bbox().unwrap();
+    for (a, b) in [
+        (exact.min_x, flat.min_x),
+        (exact.min_y, flat.min_y),
+        (exact.max_x, flat.max_x),
+        (exact.max_y, flat.max_y),
+    ] {
+        assert!((a - b).abs() < 1e-3, "{exact:?} {flat:?}");
+    }
+    assert!((exact.width() - cubic.width()).abs() < 0.1);
+    let loose = p.loose_bbox().unwrap();
+    assert!(loose.min_x <= exact.min_x && loose.max_y >= exact.max_y);
+
+    let empty = svgpath::Path::new(&[]);
+    assert_eq!(empty.bbox(), None);
+    let rect = svgpath::Rect::new(0.0, 0.0, 10.0, 10.0);
+    assert_eq!(empty.simplify().fit(&rect, true, true).to_string(), "");
+}
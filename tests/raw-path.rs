@@ -0,0 +1,136 @@
+use svgpath::RawSegment;
+
+#[test]
+fn roundtrip() {
+    let test_data = [
+        "M 0 0",
+        "  M10-5.5.5 1l1e2,-3E-1  ",
+        "m 5,7 3 4 5 6 z",
+        "M 7,9 L 100,75 h -50 z\n",
+        "M10 30a20 20 0 0 1 40 0q0 30 -40 60Z",
+        "M0 0a1 1 0 0110 10",
+        "M 1.50 2.000 c 1 2 3 4 5 6 7 8 9 10 11 12 s+1 .5 2 3",
+    ];
+    for s in test_data {
+        let rp = svgpath::parse_raw(s);
+        assert!(rp.is_ok());
+        assert_eq!(rp.unwrap().to_string(), s);
+    }
+}
+
+#[test]
+fn segments() {
+    let rp = svgpath::parse_raw("m 5,7 3 4 L 1 1 2 2 z").unwrap();
+    let segs: Vec<_> = rp
+        .segments()
+        .map(|s| (s.letter(), s.is_relative(), s.is_implicit()))
+        .collect();
+    assert_eq!(
+        segs,
+        [
+            ('m', true, false),
+            ('l', true, true),
+            ('L', false, false),
+            ('L', false, true),
+            ('z', true, false),
+        ]
+    );
+
+    let texts: Vec<_> = rp
+        .segments()
+        .next()
+        .unwrap()
+        .args()
+        .iter()
+        .map(|n| n.text())
+        .collect();
+    assert_eq!(texts, ["5", "7"]);
+}
+
+#[test]
+fn to_path() {
+    let test_data = [
+        ("m 5,7 3 4 z", "M 5 7 L 8 11 Z"),
+        ("M5,7h10v-13z", "M 5 7 H 15 V -6 Z"),
+        ("M 10 30 a 20 20 0 0 1 40 0", "M 10 30 A 20 20 0 0 1 50 30"),
+    ];
+    for (input, output) in test_data {
+        let rp = svgpath::parse_raw(input).unwrap();
+        assert_eq!(rp.to_path().to_string(), output);
+        assert_eq!(
+            rp.to_path().to_string(),
+            svgpath::parse(input).unwrap().to_string()
+        );
+    }
+}
+
+#[test]
+fn edit() {
+    let mut rp = svgpath::parse_raw("M 1.50,2 l10-5 20.5.5 Z").unwrap();
+
+    // Only the edited number changes
+    assert!(rp.segments_mut()[1].args_mut()[1].set_value(5.0));
+    assert_eq!(rp.to_string(), "M 1.50,2 l10 5 20.5.5 Z");
+
+    // Numbers stay separated after edits
+    assert!(rp.segments_mut()[2].args_mut()[0].set_value(20.0));
+    assert_eq!(rp.to_string(), "M 1.50,2 l10 5 20 .5 Z");
+
+    rp.segments_mut()
+        .insert(3, RawSegment::new('H', &[7.0]).unwrap());
+    assert_eq!(rp.to_string(), "M 1.50,2 l10 5 20 .5H 7 Z");
+    assert_eq!(
+        rp.to_path().to_string(),
+        "M 1.5 2 L 11.5 7 L 31.5 7.5 H 7 Z"
+    );
+
+    assert!(RawSegment::new('L', &[1.0]).is_none());
+    assert!(RawSegment::new('X', &[]).is_none());
+    assert!(RawSegment::new('L', &[1.0, f64::NAN]).is_none());
+    assert!(RawSegment::new('A', &[1.0, 1.0, 0.0, 2.0, 0.0, 1.0, 1.0]).is_none());
+
+    // Values that cannot be written are rejected
+    let mut rp = svgpath::parse_raw("M 0 0 A 5 5 0 1 0 10 0").unwrap();
+    let args = rp.segments_mut()[1].args_mut();
+    assert!(!args[0].set_value(f64::NAN));
+    assert!(!args[1].set_value(f64::INFINITY));
+    assert!(!args[3].set_value(0.5));
+    assert!(args[3].is_flag() && !args[2].is_flag());
+    assert!(args[4].set_value(-0.0));
+    assert_eq!(rp.to_string(), "M 0 0 A 5 5 0 1 0 10 0");
+    assert!(rp.segments_mut()[1].args_mut()[4].set_value(1.0));
+    assert_eq!(rp.to_string(), "M 0 0 A 5 5 0 1 1 10 0");
+}
+
+#[test]
+fn implicit_after_edit() {
+    // Written text and geometry agree when the explicit segment is gone
+    let mut rp = svgpath::parse_raw("M 0 0 H 1 2 l 3 3 4 4").unwrap();
+    rp.segments_mut().remove(1);
+    assert_eq!(rp.to_string(), "M 0 0H 2 l 3 3 4 4");
+    rp.segments_mut().remove(2);
+    assert_eq!(rp.to_string(), "M 0 0H 2l 4 4");
+
+    // An implicit lineto after a moveto needs no letter
+    let mut rp = svgpath::parse_raw("M 0 0 L 1 1 2 2").unwrap();
+    rp.segments_mut().remove(1);
+    assert_eq!(rp.to_string(), "M 0 0 2 2");
+
+    for s in ["M 0 0 H 1 2 l 3 3 4 4", "m 1 2 3 4 z", "M 1 2 3 4 5 6"] {
+        let mut rp = svgpath::parse_raw(s).unwrap();
+        rp.segments_mut().remove(1);
+        let written = svgpath::parse_raw(&rp.to_string()).unwrap();
+        assert_eq!(
+            written.to_path().to_string(),
+            rp.to_path().to_string(),
+            "{s}"
+        );
+    }
+}
+
+#[test]
+fn invalid() {
+    for s in ["", "  ", "M 0 0 L 5", "5 M 0 0"] {
+        assert!(svgpath::parse_raw(s).is_err());
+    }
+}
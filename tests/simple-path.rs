@@ -94,7 +94,7 @@ fn tests() {
 
         let sp = p.simplify();
         assert_eq!(sp.to_string(), d.simple);
-        assert_eq!(sp.bbox(), d.bbox);
+        assert_eq!(sp.bbox(), Some(d.bbox));
 
         let fp = sp.fit(&rect, true, true);
         assert_eq!(fp.to_string(), d.fit);
@@ -124,10 +124,6 @@ fn reverse() {
             "M 10 7 L 139 15",
             "M 139 15 L 10 7",
         ],
-        [
-            "L 10 30 L 200 100",
-            "M 200 100 L 10 30",
-        ],
         [
             "M 10,30A 20,20 0,0,1 50,30A 20,20 0,0,1 90,30Q 90,60 50,90Q 10,60 10,30Z",
             "M 10 30 C 10 50 23.33 70 50 90 C 76.67 70 90 50 90 30 C 90 18.95 81.05 10 70 10 C 58.95 10 50 18.95 50 30 C 50 18.95 41.05 10 30 10 C 18.95 10 10 18.95 10 30 Z",
@@ -142,4 +138,689 @@ fn reverse() {
         assert_eq!(rev.to_string(), expected);
         assert_eq!(rev.bbox(), sp.bbox());
     }
+
+    // Path data must start with a moveto, but commands may not
+    let p = svgpath::Path::new(&[
+        svgpath::Command::Line { x: 10.0, y: 30.0 },
+        svgpath::Command::Line { x: 200.0, y: 100.0 },
+    ]);
+    assert_eq!(p.simplify().reverse().to_string(), "M 200 100 L 10 30");
+}
+
+#[test]
+fn length() {
+    let sp = svgpath::parse("M 0 0 H 30 V 40 Z M 100 100 h 10")
+        .unwrap()
+        .simplify();
+    assert_eq!(sp.length(), 130.0);
+
+    let lengths: Vec<_> = sp.segments().map(|(i, s)| (i, s.length(1e-9))).collect();
+    assert_eq!(lengths, [(1, 30.0), (2, 40.0), (3, 50.0), (5, 10.0)]);
+
+    let tables = sp.cumulative_lengths(1e-9);
+    assert_eq!(tables, [vec![0.0, 30.0, 70.0, 120.0], vec![0.0, 10.0]]);
+
+    // Circle made of arcs, close to 2 * PI * r given the cubic approximation
+    let sp = svgpath::parse("M 10 0 A 10 10 0 0 1 -10 0 A 10 10 0 0 1 10 0 Z")
+        .unwrap()
+        .simplify();
+    let circumference = 2.0 * std::f64::consts::PI * 10.0;
+    assert!((sp.length() - circumference).abs() < 1e-2);
+    let rough = sp.length_with_tolerance(1e-2);
+    assert!((rough - sp.length()).abs() < 1e-2);
+
+    let empty = svgpath::parse("M 5 5").unwrap().simplify();
+    assert_eq!(empty.length(), 0.0);
+}
+
+#[test]
+fn point_at_length() {
+    use svgpath::Point;
+
+    let sp = svgpath::parse("M 0 0 H 30 V 40 Z").unwrap().simplify();
+    let p = |x, y| Some(Point { x, y });
+    assert_eq!(sp.point_at_length(-5.0), p(0.0, 0.0));
+    assert_eq!(sp.point_at_length(15.0), p(15.0, 0.0));
+    assert_eq!(sp.point_at_length(50.0), p(30.0, 20.0));
+    assert_eq!(sp.point_at_length(95.0), p(15.0, 20.0));
+    assert_eq!(sp.point_at_length(500.0), p(0.0, 0.0));
+
+    assert_eq!(sp.tangent_at_length(10.0), p(1.0, 0.0));
+    assert_eq!(sp.tangent_at_length(40.0), p(0.0, 1.0));
+    assert_eq!(sp.normal_at_length(10.0), p(-0.0, 1.0));
+    assert_eq!(sp.normal_at_length(40.0), p(-1.0, 0.0));
+
+    assert_eq!(sp.point_at(1, 0.5), p(15.0, 0.0));
+    assert_eq!(sp.point_at(3, 0.5), p(15.0, 20.0));
+    assert_eq!(sp.point_at(0, 0.5), None);
+
+    // Half way around a circle lands on the opposite side
+    let sp = svgpath::parse("M 10 0 A 10 10 0 0 1 -10 0 A 10 10 0 0 1 10 0")
+        .unwrap()
+        .simplify();
+    let half = sp.length() / 2.0;
+    let pt = sp.point_at_length(half).unwrap();
+    assert!((pt.x + 10.0).abs() < 1e-9 && pt.y.abs() < 1e-9);
+    let quarter = sp.point_at_length(half / 2.0).unwrap();
+    assert!(quarter.x.abs() < 1e-9 && (quarter.y - 10.0).abs() < 1e-9);
+    let tangent = sp.tangent_at_length(half / 2.0).unwrap();
+    assert!((tangent.x + 1.0).abs() < 1e-9 && tangent.y.abs() < 1e-9);
+
+    // Degenerate control points still give a direction
+    let sp = svgpath::parse("M 0 0 C 0 0 10 0 10 0").unwrap().simplify();
+    assert_eq!(sp.tangent_at_length(0.0), p(1.0, 0.0));
+
+    let sp = svgpath::parse("M 5 5").unwrap().simplify();
+    assert_eq!(sp.point_at_length(0.0), None);
+}
+
+#[test]
+fn flatten() {
+    let sp = svgpath::parse("M 10 30 A 20 20 0 0 1 50 30 L 50 60 Z M 0 0 H 5")
+        .unwrap()
+        .simplify();
+    assert!(!sp.is_flat());
+
+    let flat = sp.flatten(0.5);
+    assert!(flat.is_flat());
+    assert_eq!(flat.subpaths_count(), 2);
+    assert_eq!(
+        flat.to_string(),
+        "M 10 30 L 11.57 22.22 L 15.86 15.86 L 22.22 11.57 L 30 10 \
+        L 37.78 11.57 L 44.14 15.86 L 48.43 22.22 L 50 30 L 50 60 Z M 0 0 L 5 0"
+    );
+
+    // Finer tolerance needs more lines, lines stay untouched
+    assert!(sp.flatten(0.01).commands().count() > flat.commands().count());
+    let lines = svgpath::parse("M 0 0 L 10 10 L 20 0").unwrap().simplify();
+    assert_eq!(lines.flatten(0.1).to_string(), lines.to_string());
+
+    let polys: Vec<_> = sp.polylines(0.5).collect();
+    assert_eq!(polys.len(), 2);
+    assert!(polys[0].closed);
+    assert_eq!(polys[0].points.len(), 10);
+    assert!(!polys[1].closed);
+    assert_eq!(polys[1].points.len(), 2);
+
+    // Flattened points match the flattened path
+    let mut points = flat.commands().filter_map(|cmd| match *cmd {
+        svgpath::Command::Move { x, y } | svgpath::Command::Line { x, y } => Some((x, y)),
+        _ => None,
+    });
+    for poly in &polys {
+        for p in &poly.points {
+            assert_eq!(Some((p.x, p.y)), points.next());
+        }
+    }
+
+    // Lone moves produce nothing, drawing after a close starts a new polyline
+    let sp = svgpath::parse("M 1 1 M 0 0 L 4 0 L 4 4 Z L 0 -4")
+        .unwrap()
+        .simplify();
+    let polys: Vec<_> = sp.polylines(0.1).collect();
+    assert_eq!(polys.len(), 2);
+    assert_eq!(polys[0].points.len(), 3);
+    assert_eq!(polys[1].points.len(), 2);
+    assert_eq!((polys[1].points[0].x, polys[1].points[0].y), (0.0, 0.0));
+
+    // A curve after a close starts at the subpath start
+    let sp = svgpath::parse("M 0 0 L 10 0 L 10 10 Z C 0 20 20 20 20 0")
+        .unwrap()
+        .simplify();
+    assert_eq!(
+        sp.flatten(0.5).to_string(),
+        "M 0 0 L 10 0 L 10 10 Z L 1.11 7.35 L 3.97 12.24 L 7.87 14.69 \
+        L 12.13 14.69 L 16.03 12.24 L 18.89 7.35 L 20 0"
+    );
+}
+
+#[test]
+fn flatten_tolerance() {
+    let sp = svgpath::parse("M 0 0 C 0 10 10 10 10 0")
+        .unwrap()
+        .simplify();
+    for tolerance in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let flat = std::panic::catch_unwind(|| sp.flatten(tolerance));
+        assert!(flat.is_err(), "{tolerance}");
+        let polys = std::panic::catch_unwind(|| sp.polylines(tolerance).count());
+        assert!(polys.is_err(), "{tolerance}");
+    }
+}
+
+#[test]
+fn trim() {
+    let sp = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+
+    let (a, b) = sp.split_at_length(15.0);
+    assert_eq!(a.to_string(), "M 0 0 L 10 0 L 10 5");
+    assert_eq!(b.to_string(), "M 10 5 L 10 10 L 0 10 L 0 0");
+    assert_eq!(a.length() + b.length(), sp.length());
+
+    // Whole subpaths keep their closepath
+    assert_eq!(sp.trim(0.0, 40.0).to_string(), sp.to_string());
+    assert_eq!(sp.trim(-5.0, 100.0).to_string(), sp.to_string());
+    assert_eq!(
+        sp.trim(5.0, 35.0).to_string(),
+        "M 5 0 L 10 0 L 10 10 L 0 10 L 0 5"
+    );
+    assert_eq!(sp.trim(10.0, 10.0).commands().count(), 0);
+    assert_eq!(sp.split_at_length(40.0).1.commands().count(), 0);
+
+    // Pieces of several subpaths each start with a moveto
+    let sp = svgpath::parse("M 0 0 H 10 M 0 5 C 0 10 10 10 10 5")
+        .unwrap()
+        .simplify();
+    let len = sp.length();
+    let part = sp.trim(5.0, len - 5.0);
+    assert_eq!(part.subpaths_count(), 2);
+    assert!((part.length() - (len - 10.0)).abs() < 1e-6);
+    let end = part.point_at_length(part.length()).unwrap();
+    let expected = sp.point_at_length(len - 5.0).unwrap();
+    assert!((end.x - expected.x).abs() < 1e-9 && (end.y - expected.y).abs() < 1e-9);
+
+    let cubic = svgpath::Command::Cubic {
+        x1: 0.0,
+        y1: 10.0,
+        x2: 10.0,
+        y2: 10.0,
+        x: 10.0,
+        y: 0.0,
+    };
+    let (c1, c2) = cubic
+        .split_cubic(svgpath::Point { x: 0.0, y: 0.0 }, 0.5)
+        .unwrap();
+    assert_eq!(c1.to_string(), "C 0 5 2.5 7.5 5 7.5");
+    assert_eq!(c2.to_string(), "C 7.5 7.5 10 5 10 0");
+    assert!(
+        svgpath::Command::Close
+            .split_cubic(svgpath::Point { x: 0.0, y: 0.0 }, 0.5)
+            .is_none()
+    );
+}
+
+#[test]
+fn area() {
+    let square = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+    assert_eq!(square.area(), 100.0);
+    assert!(square.is_clockwise());
+    assert_eq!(square.reverse().area(), -100.0);
+    assert!(!square.reverse().is_clockwise());
+    let c = square.centroid().unwrap();
+    assert_eq!((c.x, c.y), (5.0, 5.0));
+
+    // Open subpaths are closed implicitly
+    let open = svgpath::parse("M 0 0 H 10 V 10 H 0").unwrap().simplify();
+    assert_eq!(open.area(), 100.0);
+
+    // Cubic approximation of a circle
+    let circle = svgpath::parse("M 20 10 A 10 10 0 0 1 0 10 A 10 10 0 0 1 20 10 Z")
+        .unwrap()
+        .simplify();
+    assert!((circle.area() - std::f64::consts::PI * 100.0).abs() < 0.1);
+    let c = circle.centroid().unwrap();
+    assert!((c.x - 10.0).abs() < 1e-9 && (c.y - 10.0).abs() < 1e-9);
+
+    // A hole wound the other way is subtracted
+    let donut = svgpath::parse("M 0 0 H 10 V 10 H 0 Z M 2 2 V 8 H 8 V 2 Z")
+        .unwrap()
+        .simplify();
+    assert_eq!(donut.area(), 64.0);
+    let parts = donut.split();
+    assert!(parts[0].is_clockwise());
+    assert!(!parts[1].is_clockwise());
+    assert_eq!(parts[1].area(), -36.0);
+
+    // Half-disc: centroid at 4r/3pi from the diameter
+    let half = svgpath::parse("M 0 0 A 10 10 0 0 1 20 0 Z")
+        .unwrap()
+        .simplify();
+    let c = half.centroid().unwrap();
+    assert!((c.x - 10.0).abs() < 1e-9);
+    assert!((c.y + 40.0 / (3.0 * std::f64::consts::PI)).abs() < 1e-2);
+
+    let line = svgpath::parse("M 0 0 L 10 10").unwrap().simplify();
+    assert_eq!(line.area(), 0.0);
+    assert!(line.centroid().is_none());
+}
+
+#[test]
+fn contains() {
+    use svgpath::FillRule::{EvenOdd, NonZero};
+
+    // Outer square and inner square wound the same way
+    let sp = svgpath::parse("M 0 0 H 10 V 10 H 0 Z M 2 2 H 8 V 8 H 2 Z")
+        .unwrap()
+        .simplify();
+    assert!(sp.contains(1.0, 1.0, NonZero));
+    assert!(sp.contains(1.0, 1.0, EvenOdd));
+    assert!(sp.contains(5.0, 5.0, NonZero));
+    assert!(!sp.contains(5.0, 5.0, EvenOdd));
+    assert!(!sp.contains(11.0, 5.0, NonZero));
+    assert!(!sp.contains(-1.0, 5.0, EvenOdd));
+
+    // Ray through a vertex counts once
+    let diamond = svgpath::parse("M 5 0 L 10 5 L 5 10 L 0 5 Z")
+        .unwrap()
+        .simplify();
+    assert!(diamond.contains(2.0, 5.0, NonZero));
+    assert!(!diamond.contains(-2.0, 5.0, EvenOdd));
+    assert!(!diamond.contains(-2.0, 0.0, EvenOdd));
+
+    // Exact on curves: a circle, tested just inside and outside its outline
+    let circle = svgpath::parse("M 20 10 A 10 10 0 0 1 0 10 A 10 10 0 0 1 20 10")
+        .unwrap()
+        .simplify();
+    let r = 10.0 / 2f64.sqrt();
+    assert!(circle.contains(10.0 + r - 0.01, 10.0 + r - 0.01, NonZero));
+    assert!(!circle.contains(10.0 + r + 0.01, 10.0 + r + 0.01, NonZero));
+    assert!(circle.contains(10.0, 19.99, EvenOdd));
+    assert!(!circle.contains(10.0, 20.01, EvenOdd));
+
+    // Open subpaths are filled as if closed
+    let open = svgpath::parse("M 0 0 L 10 0 L 10 10").unwrap().simplify();
+    assert!(open.contains(8.0, 2.0, NonZero));
+    assert!(!open.contains(2.0, 8.0, NonZero));
+}
+
+#[test]
+fn nearest_point() {
+    let sp = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+
+    let n = sp.nearest_point(5.0, -3.0).unwrap();
+    assert_eq!((n.point.x, n.point.y), (5.0, 0.0));
+    assert_eq!((n.index, n.t, n.distance), (1, 0.5, 3.0));
+
+    // Closing segment
+    let n = sp.nearest_point(-1.0, 2.0).unwrap();
+    assert_eq!((n.point.x, n.point.y, n.index), (0.0, 2.0, 4));
+
+    // Nearest point on a circle lies on the ray from its center
+    let circle = svgpath::parse("M 20 10 A 10 10 0 0 1 0 10 A 10 10 0 0 1 20 10")
+        .unwrap()
+        .simplify();
+    let n = circle.nearest_point(30.0, 30.0).unwrap();
+    let dir = (n.point.x - 10.0).atan2(n.point.y - 10.0);
+    assert!((dir - std::f64::consts::FRAC_PI_4).abs() < 1e-3);
+    assert!((n.distance - (800f64.sqrt() - 10.0)).abs() < 1e-2);
+    let p = circle.point_at(n.index, n.t).unwrap();
+    assert_eq!((p.x, p.y), (n.point.x, n.point.y));
+
+    assert!(sp.stroke_contains(5.0, 0.9, 2.0));
+    assert!(!sp.stroke_contains(5.0, 1.1, 2.0));
+    assert!(sp.stroke_contains(10.5, 10.5, 2.0));
+    assert!(circle.stroke_contains(10.0, 0.5, 1.0));
+    assert!(!circle.stroke_contains(10.0, 10.0, 1.0));
+
+    let empty = svgpath::parse("M 1 1").unwrap().simplify();
+    assert!(empty.nearest_point(0.0, 0.0).is_none());
+}
+
+#[test]
+fn intersections() {
+    let square = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+    let diagonal = svgpath::parse("M -5 5 L 15 5").unwrap().simplify();
+
+    let hits = square.intersections(&diagonal);
+    assert_eq!(hits.len(), 2);
+    assert_eq!((hits[0].point.x, hits[0].point.y), (10.0, 5.0));
+    assert_eq!((hits[0].index_a, hits[0].t_a), (2, 0.5));
+    assert_eq!((hits[0].index_b, hits[0].t_b), (1, 0.75));
+    assert_eq!((hits[1].point.x, hits[1].point.y), (0.0, 5.0));
+    assert_eq!(hits[1].index_a, 4);
+
+    // Passing through a corner is reported once
+    let corner = svgpath::parse("M -5 -5 L 5 5").unwrap().simplify();
+    let hits = square.intersections(&corner);
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].point.x, hits[0].point.y), (0.0, 0.0));
+
+    // Two circles of radius 10 with centers 10 apart
+    let circle = |cx: f64| {
+        svgpath::parse(&format!(
+            "M {} 0 A 10 10 0 0 1 {} 0 A 10 10 0 0 1 {} 0 Z",
+            cx + 10.0,
+            cx - 10.0,
+            cx + 10.0
+        ))
+        .unwrap()
+        .simplify()
+    };
+    let (a, b) = (circle(0.0), circle(10.0));
+    let hits = a.intersections(&b);
+    assert_eq!(hits.len(), 2);
+    for hit in &hits {
+        assert!((hit.point.x - 5.0).abs() < 1e-2);
+        assert!((hit.point.y.abs() - 75f64.sqrt()).abs() < 1e-2);
+        let p = a.point_at(hit.index_a, hit.t_a).unwrap();
+        let q = b.point_at(hit.index_b, hit.t_b).unwrap();
+        assert!((p.x - q.x).abs() < 1e-9 && (p.y - q.y).abs() < 1e-9);
+    }
+
+    assert!(square.intersections(&circle(50.0)).is_empty());
+}
+
+#[test]
+fn self_intersections() {
+    let square = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+    assert!(square.self_intersections().is_empty());
+    let circle = svgpath::parse("M 20 10 A 10 10 0 0 1 0 10 A 10 10 0 0 1 20 10")
+        .unwrap()
+        .simplify();
+    assert!(circle.self_intersections().is_empty());
+    assert!(circle.intersections(&circle).is_empty());
+
+    let bowtie = svgpath::parse("M 0 0 L 10 10 H 0 L 10 0 Z")
+        .unwrap()
+        .simplify();
+    let hits = bowtie.self_intersections();
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].point.x, hits[0].point.y), (5.0, 5.0));
+    assert_eq!((hits[0].index_a, hits[0].index_b), (1, 3));
+
+    // A cubic with a loop
+    let sp = svgpath::parse("M 0 0 C 20 20 -10 20 10 0")
+        .unwrap()
+        .simplify();
+    let hits = sp.self_intersections();
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].index_a, hits[0].index_b), (1, 1));
+    assert!(hits[0].t_a < hits[0].t_b);
+    let p = sp.point_at(1, hits[0].t_a).unwrap();
+    let q = sp.point_at(1, hits[0].t_b).unwrap();
+    assert!((p.x - q.x).abs() < 1e-9 && (p.y - q.y).abs() < 1e-9);
+}
+
+#[test]
+fn boolean() {
+    use svgpath::{BooleanOp, FillRule};
+
+    let a = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+    let b = svgpath::parse("M 5 5 H 15 V 15 H 5 Z").unwrap().simplify();
+
+    let union = a.union(&b);
+    assert_eq!(union.subpaths_count(), 1);
+    assert_eq!(union.area(), 175.0);
+    assert_eq!(
+        union.to_string(),
+        "M 0 0 L 10 0 L 10 5 L 15 5 L 15 15 L 5 15 L 5 10 L 0 10 Z"
+    );
+    assert_eq!(
+        a.intersection(&b).to_string(),
+        "M 10 5 L 10 10 L 5 10 L 5 5 Z"
+    );
+    assert_eq!(a.difference(&b).area(), 75.0);
+    assert_eq!(b.difference(&a).area(), 75.0);
+    let xor = a.xor(&b);
+    assert_eq!(xor.area(), 150.0);
+    assert_eq!(xor.subpaths_count(), 2);
+
+    // Input direction does not matter, output is always clockwise
+    assert_eq!(a.reverse().union(&b.reverse()).area(), 175.0);
+    assert!(a.reverse().intersection(&b).is_clockwise());
+
+    // Shared edges and identical shapes
+    let c = svgpath::parse("M 10 0 H 20 V 10 H 10 Z")
+        .unwrap()
+        .simplify();
+    assert_eq!(a.union(&c).to_string(), "M 0 0 L 20 0 L 20 10 L 0 10 Z");
+    assert_eq!(a.union(&a).area(), 100.0);
+    assert_eq!(a.intersection(&a).area(), 100.0);
+    assert_eq!(a.difference(&a).commands().count(), 0);
+
+    // Punching a hole keeps it as a counter-clockwise contour
+    let hole = svgpath::parse("M 3 3 H 7 V 7 H 3 Z").unwrap().simplify();
+    let ring = a.difference(&hole);
+    assert_eq!(ring.area(), 84.0);
+    let parts = ring.split();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts.iter().filter(|p| p.is_clockwise()).count(), 1);
+
+    // Curves stay cubic
+    let circle = svgpath::parse("M 20 10 A 10 10 0 0 1 0 10 A 10 10 0 0 1 20 10 Z")
+        .unwrap()
+        .simplify();
+    let half = circle.intersection(&svgpath::parse("M 0 0 H 10 V 20 H 0 Z").unwrap().simplify());
+    assert!(!half.is_flat());
+    assert!((half.area() - circle.area() / 2.0).abs() < 1e-6);
+
+    // The fill rule decides about the inner square of a double contour
+    let nested = svgpath::parse("M 0 0 H 10 V 10 H 0 Z M 2 2 H 8 V 8 H 2 Z")
+        .unwrap()
+        .simplify();
+    let full = nested.boolean(&b, BooleanOp::Union, FillRule::NonZero, FillRule::NonZero);
+    assert_eq!(full.area(), 175.0);
+    let framed = nested.boolean(&b, BooleanOp::Union, FillRule::EvenOdd, FillRule::NonZero);
+    assert_eq!(framed.area(), 175.0 - 27.0);
+
+    // Each operand has its own fill rule
+    let framed = b.boolean(
+        &nested,
+        BooleanOp::Union,
+        FillRule::NonZero,
+        FillRule::EvenOdd,
+    );
+    assert_eq!(framed.area(), 175.0 - 27.0);
+    let hole = nested.boolean(
+        &nested,
+        BooleanOp::Xor,
+        FillRule::NonZero,
+        FillRule::EvenOdd,
+    );
+    assert_eq!(hole.to_string(), "M 2 2 L 8 2 L 8 8 L 2 8 Z");
+
+    // Split points on straight edges do not stay in the result
+    let d = svgpath::parse("M -2 -2 H 5 V 5 H -2 Z").unwrap().simplify();
+    let e = svgpath::parse("M 0 -2 H 12 V 12 H 0 Z").unwrap().simplify();
+    assert_eq!(
+        d.union(&e).to_string(),
+        "M -2 -2 L 12 -2 L 12 12 L 0 12 L 0 5 L -2 5 Z"
+    );
+}
+
+#[test]
+fn stroke_to_path() {
+    use svgpath::FillRule::NonZero;
+    use svgpath::{LineCap, LineJoin, StrokeStyle};
+
+    let style = |cap, join| StrokeStyle {
+        width: 2.0,
+        cap,
+        join,
+        ..Default::default()
+    };
+
+    let line = svgpath::parse("M 0 0 L 10 0").unwrap().simplify();
+    let butt = line.stroke_to_path(&style(LineCap::Butt, LineJoin::Miter));
+    assert_eq!(butt.to_string(), "M 0 1 L 10 1 L 10 -1 L 0 -1 L 0 1 Z");
+    assert_eq!(butt.area().abs(), 20.0);
+    let square = line.stroke_to_path(&style(LineCap::Square, LineJoin::Miter));
+    assert_eq!(square.area().abs(), 24.0);
+    assert!(square.contains(-0.9, 0.9, NonZero));
+    let round = line.stroke_to_path(&style(LineCap::Round, LineJoin::Miter));
+    assert!((round.area().abs() - (20.0 + std::f64::consts::PI)).abs() < 1e-2);
+    assert!(round.contains(-0.9, 0.0, NonZero));
+    assert!(!round.contains(-0.9, 0.9, NonZero));
+
+    // Closed subpaths give an outer and an inner contour
+    let rect = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+    let miter = rect.stroke_to_path(&style(LineCap::Butt, LineJoin::Miter));
+    assert_eq!(miter.subpaths_count(), 2);
+    for (x, y, inside) in [
+        (-0.9, -0.9, true),
+        (0.9, 0.9, true),
+        (0.5, 5.0, true),
+        (10.5, 5.0, true),
+        (1.5, 5.0, false),
+        (1.5, 1.5, false),
+        (5.0, 5.0, false),
+        (-1.1, 5.0, false),
+    ] {
+        assert_eq!(miter.contains(x, y, NonZero), inside, "({x}, {y})");
+    }
+    let bevel = rect.stroke_to_path(&style(LineCap::Butt, LineJoin::Bevel));
+    assert!(!bevel.contains(-0.9, -0.9, NonZero));
+    assert!(bevel.contains(-0.4, -0.4, NonZero));
+    let round = rect.stroke_to_path(&style(LineCap::Butt, LineJoin::Round));
+    assert!(!round.contains(-0.8, -0.8, NonZero));
+    assert!(round.contains(-0.6, -0.6, NonZero));
+
+    // Sharp corners fall back to a bevel past the miter limit
+    let sharp = svgpath::parse("M 0 0 L 10 1 L 0 2").unwrap().simplify();
+    let s = sharp.stroke_to_path(&style(LineCap::Butt, LineJoin::Miter));
+    assert!(!s.contains(11.5, 1.0, NonZero));
+    let s = sharp.stroke_to_path(&StrokeStyle {
+        miter_limit: 100.0,
+        ..style(LineCap::Butt, LineJoin::Miter)
+    });
+    assert!(s.contains(11.5, 1.0, NonZero));
+
+    // Offset curves stay within tolerance of the true offset
+    let circle = svgpath::parse("M 20 10 A 10 10 0 0 1 0 10 A 10 10 0 0 1 20 10 Z")
+        .unwrap()
+        .simplify();
+    let ring = circle.stroke_to_path(&style(LineCap::Butt, LineJoin::Miter));
+    assert!(!ring.is_flat());
+    for poly in ring.polylines(1e-4) {
+        let r: Vec<_> = poly
+            .points
+            .iter()
+            .map(|p| (p.x - 10.0).hypot(p.y - 10.0))
+            .collect();
+        let (min, max) = r
+            .iter()
+            .fold((f64::MAX, 0.0f64), |(a, b), r| (a.min(*r), b.max(*r)));
+        let expected = if min > 10.0 { 11.0 } else { 9.0 };
+        assert!((min - expected).abs() < 2e-2 && (max - expected).abs() < 2e-2);
+    }
+
+    // A cusp has no tangent but still gets stroked
+    let cusp = svgpath::parse("M 0 0 C 100 100 0 100 100 0")
+        .unwrap()
+        .simplify();
+    let s = cusp.stroke_to_path(&style(LineCap::Butt, LineJoin::Miter));
+    assert!(s.commands().count() > 0);
+    let (b, outline) = (cusp.bbox().unwrap(), s.bbox().unwrap());
+    assert!(outline.min_x <= b.min_x && outline.max_x >= b.max_x);
+    assert!(outline.min_y <= b.min_y && outline.max_y >= b.max_y);
+    assert!(s.contains(50.0, 75.0 - 0.5, NonZero));
+
+    // Subpaths without length only draw their caps
+    let dot = svgpath::parse("M 5 5 Z").unwrap().simplify();
+    assert_eq!(
+        dot.stroke_to_path(&style(LineCap::Butt, LineJoin::Miter))
+            .commands()
+            .count(),
+        0
+    );
+    let d = dot.stroke_to_path(&style(LineCap::Round, LineJoin::Miter));
+    assert!(d.contains(5.9, 5.0, NonZero));
+    assert!((d.area().abs() - std::f64::consts::PI).abs() < 1e-2);
+    let d = dot.stroke_to_path(&style(LineCap::Square, LineJoin::Miter));
+    assert_eq!(d.area().abs(), 4.0);
+}
+
+#[test]
+fn offset() {
+    use svgpath::LineJoin;
+
+    let rect = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+    let grown = rect.offset(1.0, LineJoin::Miter);
+    assert_eq!(grown.subpaths_count(), 1);
+    assert!((grown.area() - 144.0).abs() < 1e-9);
+    assert!(grown.is_clockwise());
+    let b = grown.bbox().unwrap();
+    assert_eq!(
+        (b.min_x, b.min_y, b.max_x, b.max_y),
+        (-1.0, -1.0, 11.0, 11.0)
+    );
+
+    let rounded = rect.offset(1.0, LineJoin::Round);
+    assert!((rounded.area() - (140.0 + std::f64::consts::PI)).abs() < 1e-2);
+    let beveled = rect.offset(1.0, LineJoin::Bevel);
+    assert!((beveled.area() - 142.0).abs() < 1e-9);
+
+    let shrunk = rect.offset(-1.0, LineJoin::Miter);
+    assert!((shrunk.area() - 64.0).abs() < 1e-9);
+    assert!(rect.offset(-6.0, LineJoin::Miter).area().abs() < 1e-9);
+
+    // Concave corner of an L shape, no loops are left behind
+    let l = svgpath::parse("M 0 0 H 10 V 5 H 5 V 10 H 0 Z")
+        .unwrap()
+        .simplify();
+    let grown = l.offset(1.0, LineJoin::Miter);
+    assert_eq!(grown.subpaths_count(), 1);
+    assert!(grown.self_intersections().is_empty());
+    assert!((grown.area() - (12.0 * 7.0 + 7.0 * 5.0)).abs() < 1e-9);
+
+    // Holes grow when the outline grows
+    let ring = svgpath::parse("M 0 0 H 10 V 10 H 0 Z M 3 3 V 7 H 7 V 3 Z")
+        .unwrap()
+        .simplify();
+    let grown = ring.offset(1.0, LineJoin::Miter);
+    assert_eq!(grown.subpaths_count(), 2);
+    assert!((grown.area() - (144.0 - 4.0)).abs() < 1e-9);
+
+    // Curves are offset with cubics
+    let circle = svgpath::parse("M 20 10 A 10 10 0 0 1 0 10 A 10 10 0 0 1 20 10 Z")
+        .unwrap()
+        .simplify();
+    let grown = circle.offset(2.0, LineJoin::Round);
+    assert!(!grown.is_flat());
+    assert!((grown.area() - std::f64::consts::PI * 144.0).abs() < 0.5);
+}
+
+#[test]
+fn dash() {
+    let line = svgpath::parse("M 0 0 H 10").unwrap().simplify();
+    assert_eq!(
+        line.dash(&[3.0, 1.0], 0.0).to_string(),
+        "M 0 0 L 3 0 M 4 0 L 7 0 M 8 0 L 10 0"
+    );
+    assert_eq!(
+        line.dash(&[3.0, 1.0], 2.0).to_string(),
+        "M 0 0 L 1 0 M 2 0 L 5 0 M 6 0 L 9 0"
+    );
+    assert_eq!(
+        line.dash(&[3.0, 1.0], -1.0).to_string(),
+        "M 1 0 L 4 0 M 5 0 L 8 0 M 9 0 L 10 0"
+    );
+    // Odd patterns repeat doubled: 2 3 2 becomes 2 on, 3 off, 2 on, 2 off, 3 on, 2 off
+    assert_eq!(
+        line.dash(&[2.0, 3.0, 2.0], 0.0).to_string(),
+        "M 0 0 L 2 0 M 5 0 L 7 0 M 9 0 L 10 0"
+    );
+    // Empty dashes keep a point for caps
+    assert_eq!(
+        line.dash(&[0.0, 5.0], 0.0).to_string(),
+        "M 0 0 L 0 0 M 5 0 L 5 0 M 10 0 L 10 0"
+    );
+
+    // Invalid patterns leave the path solid
+    assert_eq!(line.dash(&[], 0.0).to_string(), line.to_string());
+    assert_eq!(line.dash(&[0.0, 0.0], 0.0).to_string(), line.to_string());
+    assert_eq!(line.dash(&[1.0, -1.0], 0.0).to_string(), line.to_string());
+
+    // Dashes run across segments and over the start of closed subpaths
+    let rect = svgpath::parse("M 0 0 H 10 V 10 H 0 Z").unwrap().simplify();
+    let dashed = rect.dash(&[12.0, 8.0], 4.0);
+    assert_eq!(
+        dashed.to_string(),
+        "M 10 6 L 10 10 L 2 10 M 0 4 L 0 0 L 8 0"
+    );
+    assert_eq!(
+        rect.dash(&[12.0, 8.0], 0.0).to_string(),
+        "M 0 0 L 10 0 L 10 2 M 10 10 L 0 10 L 0 8"
+    );
+    // A dash longer than the subpath keeps it closed
+    assert_eq!(rect.dash(&[50.0, 1.0], 0.0).to_string(), rect.to_string());
+
+    // The pattern restarts on each subpath, curves stay cubic
+    let sp = svgpath::parse("M 0 0 H 4 M 0 5 C 0 10 10 10 10 5")
+        .unwrap()
+        .simplify();
+    let parts = sp.dash(&[3.0, 1.0], 0.0).split();
+    assert_eq!(parts[0].to_string(), "M 0 0 L 3 0");
+    assert!(parts[1].to_string().starts_with("M 0 5 C "));
+    assert!((parts[1].length() - 3.0).abs() < 1e-6);
+    assert!(parts.iter().skip(1).all(|p| !p.is_flat()));
 }
@@ -0,0 +1,69 @@
+use svgpath::{Matrix, Transform, TransformList, WriteOptions};
+
+#[test]
+fn shortest() {
+    let test_data = [
+        ["", ""],
+        ["translate(10, 0)", "translate(10)"],
+        ["translate(0 0) scale(1 1) rotate(0 5 5) skewX(0)", ""],
+        ["scale(2 2) scale(2 3)", "scale(2) scale(2 3)"],
+        [
+            "rotate(30, 0, 0) rotate(30 50 50)",
+            "rotate(30) rotate(30 50 50)",
+        ],
+        ["matrix(1 0 0 1 5 -3)", "translate(5 -3)"],
+        ["matrix(2 0 0 2 0 0)", "scale(2)"],
+        ["matrix(1,0,0,1,0,0)", ""],
+        [
+            "matrix(1 2 3 4 5 6) skewY(-4.5)",
+            "matrix(1 2 3 4 5 6) skewY(-4.5)",
+        ],
+    ];
+    for [input, output] in test_data {
+        let list = TransformList::parse(input).unwrap();
+        assert_eq!(list.to_string(), output, "{input}");
+        let m = Matrix::parse(output).unwrap();
+        assert_eq!(m.to_string(), list.to_matrix().to_string(), "{input}");
+    }
+
+    let list = TransformList::parse("translate(-0.5 10) rotate(-30 0.25 0.75)").unwrap();
+    assert_eq!(
+        list.to_string_with(&WriteOptions::minified(2)),
+        "translate(-.5 10) rotate(-30 .25.75)"
+    );
+}
+
+#[test]
+fn edit() {
+    let mut list = TransformList::parse("rotate(30 50 50) translate(5)").unwrap();
+    assert_eq!(
+        list.items(),
+        [
+            Transform::Rotate {
+                angle: 30.0,
+                cx: 50.0,
+                cy: 50.0
+            },
+            Transform::Translate { tx: 5.0, ty: 0.0 },
+        ]
+    );
+    assert_eq!(
+        list.to_matrix(),
+        Matrix::parse("rotate(30 50 50) translate(5)").unwrap()
+    );
+
+    list.items_mut().remove(1);
+    list.push(Transform::SkewX(10.0));
+    list.push(Transform::Scale { sx: 2.0, sy: 2.0 });
+    assert_eq!(list.to_string(), "rotate(30 50 50) skewX(10) scale(2)");
+    assert_eq!(Transform::SkewY(-3.0).to_string(), "skewY(-3)");
+
+    let m = Matrix::new()
+        .rotate_by(30.0, 50.0, 50.0)
+        .skew_x(10.0)
+        .scale(2.0, 2.0);
+    assert_eq!(list.to_matrix(), m);
+
+    let css = TransformList::parse_css("translateY(4px) rotate(.25turn)").unwrap();
+    assert_eq!(css.to_string(), "translate(0 4) rotate(90)");
+}